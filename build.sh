@@ -15,6 +15,7 @@ function handle_package_json {
 	# Prepare a version of the package that can be used outside of VS Code.
 	# This needs to because import vscode does not work anywhere else, but we do not use it at all.
 	cp "${dir_wasmpack}/icie_bg.js" "${dir_wasmpack}/icie_bg.wasm" "${dir_wasmpack}/package.json" "${dir_genpackagejson}/"
+	rsync -r "${dir_wasmpack}/snippets" "${dir_genpackagejson}"
 	cat "${dir_wasmpack}/icie.js" | sed 's/^.*require.*vscode.*$//gm' | sed 's/^.*require.*keytar.*$//g'  > "${dir_genpackagejson}/icie.js"
 
 	# Generate fresh package.json.
@@ -60,19 +61,27 @@ else
 	wasmopt_profile="-g -O0"
 fi
 
+# Enable optional Cargo features, e.g. BUILDSH_FEATURES=mock adds a mock judge for offline testing.
+if [ "${BUILDSH_FEATURES}" ] ; then
+	wasmpack_features="-- --features ${BUILDSH_FEATURES}"
+fi
+
 # Set up necessary directories.
 mkdir -p "${dir_root}/" "${dir_vscode}/" "${dir_build}/" "${dir_wasmpack}/" "${dir_genpackagejson}/"
 
 # Compile Rust code with wasm-pack.
 # The artifacts live in target/wasm32-unknown-unknown, but wasm-pack processes and copies some to ${dir_wasmpack}.
-# Do not quote profile, so it's not interpreted as an empty flag.
+# Do not quote profile and features, so they are not interpreted as empty flags.
 cd "${dir_root}/"
-X wasm-pack build -d "${dir_wasmpack}" -t nodejs -m no-install ${wasmpack_profile}
+X wasm-pack build -d "${dir_wasmpack}" -t nodejs -m no-install ${wasmpack_profile} ${wasmpack_features}
 
 # Copy the generated WebAssembly and glue files to the target directory.
 # This step may change if WASM some proposals progress further.
 cp "${dir_wasmpack}/icie_bg.js" "${dir_wasmpack}/icie_bg.wasm" "${dir_vscode}/"
 
+# Copy the JavaScript snippets of Rust crates, which the glue files import.
+X rsync -r "${dir_wasmpack}/snippets" "${dir_vscode}"
+
 # Copy and optimize the generated WebAssembly.
 X wasm-opt ${wasmopt_profile} -o "${dir_vscode}/icie_bg.wasm" "${dir_wasmpack}/icie_bg.wasm"
 
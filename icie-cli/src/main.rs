@@ -0,0 +1,249 @@
+//! Command-line interface to the online judge integrations used by ICIE, for use in terminals and
+//! scripts without VS Code.
+
+mod net;
+mod session;
+
+use icie_common::Manifest;
+use net::{interpret_url, BackendMeta, Session, BACKENDS};
+use std::{error::Error, fs, path::PathBuf, time::Duration};
+use structopt::StructOpt;
+use unijudge::{
+	boxed::{BoxedContestDetails, BoxedTask}, chrono::Local, Backend, RejectionCause, Resource, TaskDetails, Verdict
+};
+
+pub type R<T> = Result<T, Box<dyn Error>>;
+
+const TRACK_DELAY: Duration = Duration::from_secs(5);
+const TRACK_NOT_SEEN_RETRY_LIMIT: usize = 4;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "icie", about = "Competitive programming tools for the terminal")]
+enum Command {
+	/// Downloads the task examples and metadata into a new project directory
+	Init {
+		url: String,
+		/// Project directory, named after the task by default
+		#[structopt(long, parse(from_os_str))]
+		dir: Option<PathBuf>,
+	},
+	/// Lists contests on all sites that support them
+	Contests,
+	/// Logs in to the site and saves the session
+	Login { url: String },
+	/// Submits a C++ solution and tracks its verdict
+	Submit {
+		url: String,
+		#[structopt(parse(from_os_str))]
+		file: PathBuf,
+		/// Language name as shown on the site, defaults to a C++ version
+		#[structopt(long)]
+		language: Option<String>,
+		/// Exit right after submitting instead of waiting for the verdict
+		#[structopt(long)]
+		no_track: bool,
+	},
+	/// Waits until the submission is judged and prints its verdict
+	Track { url: String, id: String },
+}
+
+#[tokio::main(single_thread)]
+async fn main() {
+	if let Err(e) = run(Command::from_args()).await {
+		eprintln!("error: {}", e);
+		let mut source = e.source();
+		while let Some(e) = source {
+			eprintln!("caused by: {}", e);
+			source = e.source();
+		}
+		std::process::exit(1);
+	}
+}
+
+async fn run(command: Command) -> R<()> {
+	match command {
+		Command::Init { url, dir } => init(&url, dir).await,
+		Command::Contests => contests().await,
+		Command::Login { url } => connect_task(&url).await?.0.login().await,
+		Command::Submit { url, file, language, no_track } => {
+			submit(&url, &fs::read_to_string(&file)?, language, no_track).await
+		},
+		Command::Track { url, id } => {
+			let (sess, task, _) = connect_task(&url).await?;
+			track(&sess, &task, &id, TRACK_DELAY).await?;
+			Ok(())
+		},
+	}
+}
+
+async fn connect_task(url: &str) -> R<(Session, BoxedTask, &'static BackendMeta)> {
+	let (url, backend) = interpret_url(url)?;
+	let task = match url.resource {
+		Resource::Task(task) => task,
+		Resource::Contest(_) => return Err("expected a task URL, found a contest URL".into()),
+	};
+	let sess = Session::connect(&url.domain, backend).await?;
+	Ok((sess, task, backend))
+}
+
+async fn init(url: &str, dir: Option<PathBuf>) -> R<()> {
+	let (sess, task, _) = connect_task(url).await?;
+	let details = sess.run(|backend, sess| backend.task_details(sess, &task)).await?;
+	let dir = dir.unwrap_or_else(|| PathBuf::from(project_name(&details)));
+	let examples_dir = dir.join("tests").join("example");
+	fs::create_dir_all(&examples_dir)?;
+	for (i, example) in details.examples.iter().flatten().enumerate() {
+		fs::write(examples_dir.join(format!("{}.in", i + 1)), &example.input)?;
+		fs::write(examples_dir.join(format!("{}.out", i + 1)), &example.output)?;
+	}
+	// The manifest is shared with the extension, so the project can be opened in VS Code.
+	let manifest = Manifest::new(Some(url.to_owned()), Some(&details));
+	fs::write(dir.join(".icie"), serde_json::to_string(&manifest)?)?;
+	println!("{}", dir.display());
+	Ok(())
+}
+
+fn project_name(details: &TaskDetails) -> String {
+	let mut title = String::new();
+	for c in details.title.chars() {
+		if c.is_alphanumeric() {
+			title.extend(c.to_lowercase());
+		} else if !title.ends_with('-') && !title.is_empty() {
+			title.push('-');
+		}
+	}
+	format!("{}-{}", details.id.to_uppercase(), title.trim_end_matches('-'))
+}
+
+async fn contests() -> R<()> {
+	net::configure_sites()?;
+	let mut contests = Vec::new();
+	for backend in BACKENDS.iter().filter(|backend| backend.backend.supports_contests()) {
+		for domain in backend.backend.accepted_domains() {
+			// One site being down should not hide the contests on all the other ones.
+			match site_contests(domain, backend).await {
+				Ok(site_contests) => {
+					for contest in site_contests {
+						let url = backend.backend.contest_url(&contest.id);
+						contests.push((contest.start, contest.title, url));
+					}
+				},
+				Err(e) => eprintln!("warning: failed to fetch {} contests, {}", domain, e),
+			}
+		}
+	}
+	contests.sort_by_key(|contest| contest.0);
+	for (start, title, url) in contests {
+		let start = match start {
+			Some(start) => start.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+			None => "started".to_owned(),
+		};
+		println!("{}\t{}\t{}", start, title, url);
+	}
+	Ok(())
+}
+
+async fn site_contests(domain: &str, backend: &'static BackendMeta) -> R<Vec<BoxedContestDetails>> {
+	let sess = Session::connect(domain, backend).await?;
+	sess.run(|backend, sess| backend.contests(sess)).await
+}
+
+async fn submit(url: &str, code: &str, language: Option<String>, no_track: bool) -> R<()> {
+	let (sess, task, backend) = connect_task(url).await?;
+	let language = language.as_deref().unwrap_or(backend.cpp);
+	let languages = sess.run(|backend, sess| backend.task_languages(sess, &task)).await?;
+	let language = languages.iter().find(|lang| lang.name == language).ok_or_else(|| {
+		let available = languages.iter().map(|lang| lang.name.as_str()).collect::<Vec<_>>();
+		format!("language {:?} not found, available are {:?}", language, available)
+	})?;
+	let id = sess.run(|backend, sess| backend.task_submit(sess, &task, language, code)).await?;
+	println!("{}", sess.backend.submission_url(&sess.session, &task, &id));
+	if !no_track {
+		track(&sess, &task, &id, TRACK_DELAY).await?;
+	}
+	Ok(())
+}
+
+/// Prints the verdict every time it changes, and returns it once the submission is judged. The
+/// status is checked again after each delay.
+async fn track(sess: &Session, task: &BoxedTask, id: &str, delay: Duration) -> R<Verdict> {
+	let mut last_verdict = None;
+	let mut not_seen_retry_limit = TRACK_NOT_SEEN_RETRY_LIMIT;
+	loop {
+		let submissions = sess.run(|backend, sess| backend.task_submissions(sess, task)).await?;
+		match submissions.into_iter().find(|submission| submission.id == id) {
+			Some(submission) => {
+				if Some(&submission.verdict) != last_verdict.as_ref() {
+					println!("{}", fmt_verdict(&submission.verdict));
+				}
+				if let Verdict::Pending { .. } = submission.verdict {
+					last_verdict = Some(submission.verdict);
+				} else {
+					break Ok(submission.verdict);
+				}
+			},
+			None if not_seen_retry_limit > 0 => not_seen_retry_limit -= 1,
+			None => break Err(format!("submission {} not found on status page", id).into()),
+		}
+		tokio::timer::delay_for(delay).await;
+	}
+}
+
+fn fmt_verdict(verdict: &Verdict) -> String {
+	match verdict {
+		Verdict::Scored { score, max, cause, test } => format!(
+			"Scored {}{}{}",
+			score,
+			max.map(|max| format!(" out of {}", max)).unwrap_or_default(),
+			fmt_rejection(cause, test)
+		),
+		Verdict::Accepted => "Accepted".to_owned(),
+		Verdict::Rejected { cause, test } => format!("Rejected{}", fmt_rejection(cause, test)),
+		Verdict::Pending { test } => format!("Pending{}", fmt_test(test)),
+		Verdict::Skipped => "Skipped".to_owned(),
+		Verdict::Glitch => "Glitched".to_owned(),
+	}
+}
+
+fn fmt_rejection(cause: &Option<RejectionCause>, test: &Option<String>) -> String {
+	let cause = match cause {
+		Some(RejectionCause::WrongAnswer) => " due to a Wrong Answer",
+		Some(RejectionCause::RuntimeError) => " due to a Runtime Error",
+		Some(RejectionCause::TimeLimitExceeded) => " due to a Time Limit Exceeded",
+		Some(RejectionCause::MemoryLimitExceeded) => " due to a Memory Limit Exceeded",
+		Some(RejectionCause::RuleViolation) => " due to a Rule Violation",
+		Some(RejectionCause::SystemError) => " due to a System Error",
+		Some(RejectionCause::CompilationError) => " due to a Compilation Error",
+		Some(RejectionCause::IdlenessLimitExceeded) => " due to an Idleness Limit Exceeded",
+		None if test.is_some() => " failing",
+		None => "",
+	};
+	format!("{}{}", cause, fmt_test(test))
+}
+
+fn fmt_test(test: &Option<String>) -> String {
+	test.as_ref().map(|test| format!(" on {}", test)).unwrap_or_default()
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_mock_submit_and_track() {
+	use unijudge_mock::{set_scenario, Scenario};
+	set_scenario(Scenario {
+		require_login: false,
+		verdicts: vec![Verdict::Pending { test: None }, Verdict::Accepted],
+		failures: Vec::new(),
+		..Scenario::default()
+	});
+	// The user's real sessions must not be read or overwritten.
+	let sessions =
+		std::env::temp_dir().join(format!("icie-cli-mock-sessions-{}.json", std::process::id()));
+	std::env::set_var("ICIE_SESSIONS", &sessions);
+	let url = "https://mock.icie.invalid/contest/running/problem/A";
+	submit(url, "int main() {}\n", None, true).await.unwrap();
+	// Tracking happens in a new session, like running `icie track` after `icie submit --no-track`.
+	let (sess, task, _) = connect_task(url).await.unwrap();
+	let verdict = track(&sess, &task, "1", Duration::from_secs(0)).await.unwrap();
+	assert_eq!(verdict, Verdict::Accepted);
+	assert!(!sessions.exists());
+}
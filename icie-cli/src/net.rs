@@ -0,0 +1,116 @@
+use crate::{session, R};
+pub use icie_common::{BackendMeta, BACKENDS};
+use std::{
+	fs, future::Future, io::{self, BufRead, Write}, time::Duration
+};
+use unijudge::{
+	boxed::{BoxedSession, BoxedURL, DynamicBackend}, http::{Client, Settings}, reqwest::Url, Backend
+};
+
+const USER_AGENT: &str =
+	concat!("ICIE-CLI/", env!("CARGO_PKG_VERSION"), " (+https://github.com/pustaczek/icie)");
+
+pub struct Session {
+	pub backend: &'static dyn DynamicBackend,
+	pub session: BoxedSession,
+	site: String,
+}
+
+/// Passes the self-hosted sites from the environment to their backends, so that their domains are
+/// recognized.
+pub fn configure_sites() -> R<()> {
+	let dmoj = std::env::var("ICIE_DMOJ_DOMAINS").unwrap_or_default();
+	unijudge_dmoj::set_domains(dmoj.split_whitespace());
+	let domjudge = std::env::var("ICIE_DOMJUDGE_SITES").unwrap_or_default();
+	unijudge_domjudge::set_sites(domjudge.split_whitespace())?;
+	Ok(())
+}
+
+/// Reads the network settings from the environment.
+fn client_settings() -> R<Settings> {
+	let proxy = match std::env::var("ICIE_PROXY") {
+		Ok(proxy) if !proxy.is_empty() => Some(
+			proxy.parse::<Url>().map_err(|e| format!("invalid proxy URL {:?}, {}", proxy, e))?,
+		),
+		_ => None,
+	};
+	let root_certificate = match std::env::var_os("ICIE_ROOT_CERTIFICATE") {
+		Some(path) if !path.is_empty() => Some(fs::read(path)?),
+		_ => None,
+	};
+	let timeout = match std::env::var("ICIE_REQUEST_TIMEOUT") {
+		Ok(timeout) if !timeout.is_empty() => Some(Duration::from_secs(timeout.parse()?)),
+		_ => None,
+	};
+	Ok(Settings { proxy, root_certificate, timeout })
+}
+
+pub fn interpret_url(url: &str) -> R<(BoxedURL, &'static BackendMeta)> {
+	configure_sites()?;
+	for backend in BACKENDS {
+		if let Some(url) = backend.backend.deconstruct_url(url)? {
+			return Ok((url, backend));
+		}
+	}
+	Err(format!("not yet supporting contests/tasks on site {}", url).into())
+}
+
+impl Session {
+	pub async fn connect(domain: &str, backend: &'static BackendMeta) -> R<Session> {
+		let backend = backend.backend;
+		let client = Client::new(USER_AGENT, &client_settings()?)?;
+		let session = backend.connect(client, domain);
+		let site = format!("https://{}", domain);
+		if let Some(auth) = session::load(&site)? {
+			if let Ok(auth) = backend.auth_deserialize(&auth) {
+				match backend.auth_restore(&session, &auth).await {
+					Ok(())
+					| Err(unijudge::Error::WrongData)
+					| Err(unijudge::Error::WrongCredentials)
+					| Err(unijudge::Error::AccessDenied) => (),
+					Err(e) => return Err(e.into()),
+				}
+			}
+		}
+		Ok(Session { backend, session, site })
+	}
+
+	/// Runs the operation, asking for credentials and retrying if the site requires logging in.
+	pub async fn run<'f, Y, F: Future<Output=unijudge::Result<Y>>+'f>(
+		&'f self,
+		mut f: impl FnMut(&'static dyn DynamicBackend, &'f BoxedSession) -> F+'f,
+	) -> R<Y>
+	{
+		loop {
+			match f(self.backend, &self.session).await {
+				Ok(y) => break Ok(y),
+				Err(unijudge::Error::WrongCredentials) | Err(unijudge::Error::AccessDenied) => {
+					eprintln!("Access denied, logging in to {}", self.site);
+					self.login().await?
+				},
+				Err(e) => break Err(e.into()),
+			}
+		}
+	}
+
+	pub async fn login(&self) -> R<()> {
+		let username = ask(&format!("Username at {}: ", self.site))?;
+		let password = rpassword::read_password_from_tty(Some(&format!(
+			"Password for {} at {}: ",
+			username, self.site
+		)))?;
+		self.backend.auth_login(&self.session, &username, &password).await?;
+		if let Some(cache) = self.backend.auth_cache(&self.session).await? {
+			session::save(&self.site, &self.backend.auth_serialize(&cache)?)?;
+		}
+		Ok(())
+	}
+}
+
+fn ask(prompt: &str) -> R<String> {
+	eprint!("{}", prompt);
+	io::stderr().flush()?;
+	let mut line = String::new();
+	io::stdin().lock().read_line(&mut line)?;
+	Ok(line.trim().to_owned())
+}
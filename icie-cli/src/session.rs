@@ -0,0 +1,55 @@
+//! Sessions are stored in a JSON file in the user's configuration directory, mapping site URLs to
+//! serialized authentication data, so that logging in is only necessary once.
+
+use crate::R;
+use std::{
+	collections::HashMap, fs, io::Write, path::{Path, PathBuf}
+};
+
+pub fn load(site: &str) -> R<Option<String>> {
+	Ok(load_all()?.remove(site))
+}
+
+pub fn save(site: &str, auth: &str) -> R<()> {
+	let mut sessions = load_all()?;
+	sessions.insert(site.to_owned(), auth.to_owned());
+	let path = path()?;
+	fs::create_dir_all(path.parent().unwrap())?;
+	let mut file = create_private(&path)?;
+	file.write_all(serde_json::to_string_pretty(&sessions)?.as_bytes())?;
+	Ok(())
+}
+
+fn load_all() -> R<HashMap<String, String>> {
+	let path = path()?;
+	if !path.exists() {
+		return Ok(HashMap::new());
+	}
+	let sessions = serde_json::from_str(&fs::read_to_string(&path)?)
+		.map_err(|e| format!("{} is not a valid session file, {}", path.display(), e))?;
+	Ok(sessions)
+}
+
+/// Returns the session file path, which can be changed with the ICIE_SESSIONS environment variable.
+fn path() -> R<PathBuf> {
+	match std::env::var_os("ICIE_SESSIONS") {
+		Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+		_ => {
+			let config = dirs::config_dir().ok_or("could not find the configuration directory")?;
+			Ok(config.join("icie").join("sessions.json"))
+		},
+	}
+}
+
+/// Opens the file for writing, making sure it is only accessible to its owner before anything is
+/// written to it.
+#[cfg(unix)]
+fn create_private(path: &Path) -> R<fs::File> {
+	use std::os::unix::fs::OpenOptionsExt;
+	Ok(fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?)
+}
+
+#[cfg(not(unix))]
+fn create_private(path: &Path) -> R<fs::File> {
+	Ok(fs::File::create(path)?)
+}
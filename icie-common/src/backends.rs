@@ -0,0 +1,34 @@
+use unijudge::boxed::DynamicBackend;
+
+pub static BACKENDS: &[BackendMeta] = &[
+	BackendMeta::new(&unijudge_atcoder::AtCoder, "C++14 (GCC 5.4.1)", "atcoder"),
+	BackendMeta::new(&unijudge_codechef::CodeChef, "C++14(gcc 6.3)", "codechef"),
+	BackendMeta::new(&unijudge_cses::CSES, "C++17", "cses"),
+	BackendMeta::new(&unijudge_codeforces::Codeforces, "GNU G++17 7.3.0", "codeforces"),
+	BackendMeta::new(&unijudge_dmoj::DMOJ, "C++17", "dmoj"),
+	BackendMeta::new(&unijudge_domjudge::DOMjudge, "C++", "domjudge"),
+	BackendMeta::new(&unijudge_kattis::Kattis, "C++", "kattis"),
+	BackendMeta::new(&unijudge_sio2::Sio2, "C++", "sio2"),
+	BackendMeta::new(&unijudge_spoj::SPOJ, "C++14 (clang 8.0)", "spoj"),
+	#[cfg(feature = "mock")]
+	BackendMeta::new(&unijudge_mock::Mock, "C++", "mock"),
+];
+
+#[derive(Debug)]
+pub struct BackendMeta {
+	pub backend: &'static dyn DynamicBackend,
+	/// Name of the C++ language on the site, used when submitting.
+	pub cpp: &'static str,
+	pub telemetry_id: &'static str,
+}
+
+impl BackendMeta {
+	const fn new(
+		backend: &'static dyn DynamicBackend,
+		cpp: &'static str,
+		telemetry_id: &'static str,
+	) -> BackendMeta
+	{
+		BackendMeta { backend, cpp, telemetry_id }
+	}
+}
@@ -0,0 +1,63 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use unijudge::Statement;
+
+/// Checkers available without writing a checker program, selected per project in the manifest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BuiltinChecker {
+	FreeWhitespace,
+	Epsilon { absolute: f64, relative: f64 },
+	CaseInsensitive,
+	UnorderedLines,
+	UnorderedTokens,
+}
+
+/// Guesses which checker should be used based on the task statement. Currently, this only detects
+/// tasks which accept answers with a given absolute or relative error.
+pub fn infer_checker(statement: &Statement) -> Option<BuiltinChecker> {
+	let html = match statement {
+		Statement::HTML { html } => html,
+		Statement::PDF { .. } => return None,
+	};
+	let text = (&TAG_RE as &Regex).replace_all(html, "").to_lowercase();
+	let mention = text
+		.find("absolute or relative error")
+		.or_else(|| text.find("relative or absolute error"))?;
+	let nearby = text[mention..].chars().take(300).collect::<String>();
+	let epsilon = (&EPSILON_RE as &Regex)
+		.captures(&nearby)
+		.and_then(|cap| format!("1e-{}", &cap[1]).parse::<f64>().ok())
+		.unwrap_or(1e-6);
+	Some(BuiltinChecker::Epsilon { absolute: epsilon, relative: epsilon })
+}
+
+lazy_static::lazy_static! {
+	static ref TAG_RE: Regex = Regex::new("<[^>]*>").unwrap();
+	static ref EPSILON_RE: Regex =
+		Regex::new("(?:10\\s*\\^?\\s*\\{?\\s*(?:-|−|&minus;)\\s*|1e-)(\\d+)").unwrap();
+}
+
+#[test]
+fn test_infer_checker() {
+	let codeforces = Statement::HTML {
+		html: "Your answer is considered correct if its absolute or relative error does not exceed \
+		       10<sup class=\"upper-index\">-9</sup>."
+			.to_owned(),
+	};
+	assert_eq!(
+		infer_checker(&codeforces),
+		Some(BuiltinChecker::Epsilon { absolute: 1e-9, relative: 1e-9 })
+	);
+	let atcoder = Statement::HTML {
+		html: "<p>Your output is judged correct when the absolute or relative error from the \
+		       judge's output is at most <var>10^{-6}</var>.</p>"
+			.to_owned(),
+	};
+	assert_eq!(
+		infer_checker(&atcoder),
+		Some(BuiltinChecker::Epsilon { absolute: 1e-6, relative: 1e-6 })
+	);
+	let exact = Statement::HTML { html: "<p>Print the answer modulo 10^9+7.</p>".to_owned() };
+	assert_eq!(infer_checker(&exact), None);
+}
@@ -0,0 +1,12 @@
+//! Parts of ICIE shared by the VS Code extension and icie-cli, so that both support the same sites
+//! and create projects in the same format.
+
+#![feature(const_fn)]
+
+mod backends;
+mod checker;
+mod manifest;
+
+pub use backends::{BackendMeta, BACKENDS};
+pub use checker::{infer_checker, BuiltinChecker};
+pub use manifest::Manifest;
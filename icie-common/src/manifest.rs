@@ -0,0 +1,48 @@
+use crate::{infer_checker, BuiltinChecker};
+use serde::{Deserialize, Serialize};
+use unijudge::{Statement, TaskDetails};
+
+/// Project metadata saved in the .icie file in the project directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+	#[serde(default)]
+	pub task_url: Option<String>,
+	#[serde(default)]
+	pub statement: Option<Statement>,
+	#[serde(default)]
+	pub checker: Option<BuiltinChecker>,
+	/// Time limit stated in the task, in milliseconds.
+	#[serde(default)]
+	pub time_limit: Option<u64>,
+	/// Memory limit stated in the task, in bytes.
+	#[serde(default)]
+	pub memory_limit: Option<u64>,
+	#[serde(default)]
+	pub input_file: Option<String>,
+	#[serde(default)]
+	pub output_file: Option<String>,
+	#[serde(default)]
+	pub interactive: bool,
+	/// Account profile used for this project instead of the one selected for the site.
+	#[serde(default)]
+	pub profile: Option<String>,
+}
+
+impl Manifest {
+	/// Describes a new project for a task, guessing the checker from the task statement.
+	pub fn new(task_url: Option<String>, meta: Option<&TaskDetails>) -> Manifest {
+		let statement = meta.and_then(|meta| meta.statement.clone());
+		let checker = statement.as_ref().and_then(infer_checker);
+		Manifest {
+			task_url,
+			statement,
+			checker,
+			time_limit: meta.and_then(|meta| meta.time_limit).map(|limit| limit.as_millis() as u64),
+			memory_limit: meta.and_then(|meta| meta.memory_limit),
+			input_file: meta.and_then(|meta| meta.input_file.clone()),
+			output_file: meta.and_then(|meta| meta.output_file.clone()),
+			interactive: meta.map_or(false, |meta| meta.interactive),
+			profile: None,
+		}
+	}
+}
@@ -54,6 +54,9 @@ pub mod child_process {
 		#[wasm_bindgen(method, js_name = on)]
 		pub fn on_2(this: &ChildProcess, event: &str, callback: &JsValue);
 
+		#[wasm_bindgen(method, getter)]
+		pub fn pid(this: &ChildProcess) -> Option<u32>;
+
 		#[wasm_bindgen(method, getter)]
 		pub fn stdin(this: &ChildProcess) -> Option<Writable>;
 
@@ -146,15 +149,21 @@ pub mod fs {
 
 		pub fn readdir(path: &str, options: ReaddirOptions, callback: JsValue);
 
+		#[wasm_bindgen(js_name = readdirSync, catch)]
+		pub fn readdir_sync(path: &str) -> Result<js_sys::Array, JsValue>;
+
 		#[wasm_bindgen(js_name = readFile)]
 		pub fn read_file(path: &str, options: ReadFileOptions, callback: JsValue);
 
+		#[wasm_bindgen(js_name = rmdirSync, catch)]
+		pub fn rmdir_sync(path: &str) -> Result<(), JsValue>;
+
 		pub fn stat(path: &str, options: StatOptions, callback: JsValue);
 
 		pub fn unlink(path: &str, callback: JsValue);
 
-		#[wasm_bindgen(js_name = unlinkSync)]
-		pub fn unlink_sync(path: &str);
+		#[wasm_bindgen(js_name = unlinkSync, catch)]
+		pub fn unlink_sync(path: &str) -> Result<(), JsValue>;
 
 		#[wasm_bindgen(js_name = writeFile)]
 		pub fn write_file(file: &str, data: Buffer, options: WriteFileOptions, callback: JsValue);
@@ -202,6 +211,51 @@ pub mod fs {
 	wasm_abi_serde!(WriteFileOptions<'_>);
 }
 
+/// Node.js [http](https://nodejs.org/api/http.html)
+pub mod http {
+
+	use crate::stream::Readable;
+	use wasm_bindgen::prelude::*;
+
+	#[wasm_bindgen(module = http)]
+	extern "C" {
+
+		pub type Server;
+
+		/// Node.js [http.createServer](https://nodejs.org/api/http.html#http_http_createserver_options_requestlistener)
+		#[wasm_bindgen(js_name = createServer)]
+		pub fn create_server(request_listener: &JsValue) -> Server;
+
+		#[wasm_bindgen(method)]
+		pub fn close(this: &Server);
+
+		#[wasm_bindgen(method)]
+		pub fn listen(this: &Server, port: u16, host: &str);
+
+		#[wasm_bindgen(method, js_name = on)]
+		pub fn on_1(this: &Server, event: &str, callback: &JsValue);
+
+		#[wasm_bindgen(extends = Readable)]
+		pub type IncomingMessage;
+
+		#[wasm_bindgen(method, getter)]
+		pub fn method(this: &IncomingMessage) -> String;
+
+		/// Request headers, with lowercase names as keys.
+		#[wasm_bindgen(method, getter)]
+		pub fn headers(this: &IncomingMessage) -> JsValue;
+
+		pub type ServerResponse;
+
+		#[wasm_bindgen(method, js_name = writeHead)]
+		pub fn write_head(this: &ServerResponse, status_code: u16);
+
+		#[wasm_bindgen(method)]
+		pub fn end(this: &ServerResponse);
+
+	}
+}
+
 /// Node.js [os](https://nodejs.org/api/os.html)
 pub mod os {
 
@@ -210,6 +264,9 @@ pub mod os {
 	#[wasm_bindgen(module = os)]
 	extern "C" {
 
+		/// Node.js [os.cpus](https://nodejs.org/api/os.html#os_os_cpus)
+		pub fn cpus() -> js_sys::Array;
+
 		/// Node.js [os.homedir](https://nodejs.org/api/os.html#os_os_homedir)
 		pub fn homedir() -> String;
 
@@ -287,6 +344,9 @@ pub mod stream {
 
 		pub type Writable;
 
+		#[wasm_bindgen(method, js_name = on)]
+		pub fn on_1(this: &Writable, event: &str, callback: &JsValue);
+
 		#[wasm_bindgen(method)]
 		pub fn end(this: &Writable, chunk: &Buffer, encoding: (), callback: JsValue);
 
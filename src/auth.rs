@@ -1,25 +1,36 @@
-use crate::{telemetry::TELEMETRY, util::is_installed};
-use evscode::{E, R};
+use crate::{
+	manifest::{Manifest, ManifestExt}, net::interpret_url, telemetry::TELEMETRY, util::is_installed
+};
+use evscode::{quick_pick, state::Scope, QuickPick, State, E, R};
+use std::collections::HashMap;
 use wasm_bindgen_futures::JsFuture;
 
 // TODO: check how errors work w/o libsecret/gnome-keyring
 
+/// Names of the profiles created on each site, which allow keeping several accounts on one site.
+/// The default profile has no name and is not listed here.
+const PROFILES: State<HashMap<String, Vec<String>>> =
+	State::new("icie.auth.profiles", Scope::Global);
+/// Profile selected on each site. Sites which are not present use the default profile.
+const ACTIVE_PROFILES: State<HashMap<String, String>> =
+	State::new("icie.auth.activeProfiles", Scope::Global);
+
 #[derive(serde::Deserialize, serde::Serialize)]
 struct Credentials {
 	username: String,
 	password: String,
 }
 
-pub async fn get_force_ask(site: &str) -> R<(String, String)> {
+pub async fn get_force_ask(site: &str, profile: Option<&str>) -> R<(String, String)> {
 	TELEMETRY.auth_ask.spark();
-	let message = format!("Username at {}", site);
+	let message = format!("Username at {}", fmt_account(site, profile));
 	let username = evscode::InputBox::new()
 		.prompt(&message)
 		.ignore_focus_out()
 		.show()
 		.await
 		.ok_or_else(E::cancel)?;
-	let message = format!("Password for {} at {}", username, site);
+	let message = format!("Password for {} at {}", username, fmt_account(site, profile));
 	let password = evscode::InputBox::new()
 		.prompt(&message)
 		.password()
@@ -27,7 +38,7 @@ pub async fn get_force_ask(site: &str) -> R<(String, String)> {
 		.show()
 		.await
 		.ok_or_else(E::cancel)?;
-	let kr = Keyring::new("credentials", site);
+	let kr = Keyring::new("credentials", site, profile);
 	if !kr
 		.set(
 			&serde_json::to_string(&Credentials {
@@ -46,44 +57,128 @@ pub async fn get_force_ask(site: &str) -> R<(String, String)> {
 	Ok((username, password))
 }
 
-pub async fn get_cached_or_ask(site: &str) -> R<(String, String)> {
-	let kr = Keyring::new("credentials", site);
+pub async fn get_cached_or_ask(site: &str, profile: Option<&str>) -> R<(String, String)> {
+	let kr = Keyring::new("credentials", site, profile);
 	match kr.get().await {
 		Some(encoded) => {
 			let creds: Credentials = serde_json::from_str(&encoded).unwrap();
 			Ok((creds.username, creds.password))
 		},
-		None => get_force_ask(site).await,
+		None => get_force_ask(site, profile).await,
 	}
 }
 
-pub async fn get_if_cached(site: &str) -> Option<String> {
-	Keyring::new("session", site).get().await
+pub async fn get_if_cached(site: &str, profile: Option<&str>) -> Option<String> {
+	Keyring::new("session", site, profile).get().await
+}
+
+pub async fn save_cache(site: &str, profile: Option<&str>, value: &str) {
+	Keyring::new("session", site, profile).set(value).await; // ignore save fail
 }
 
-pub async fn save_cache(site: &str, value: &str) {
-	Keyring::new("session", site).set(value).await; // ignore save fail
+pub async fn has_any_saved(site: &str, profile: Option<&str>) -> bool {
+	Keyring::new("session", site, profile).get().await.is_some()
+		|| Keyring::new("credentials", site, profile).get().await.is_some()
 }
 
-pub async fn has_any_saved(site: &str) -> bool {
-	Keyring::new("session", site).get().await.is_some()
-		|| Keyring::new("credentials", site).get().await.is_some()
+/// Returns the profile that should be used on the site. The profile set in the manifest of the
+/// current project takes precedence over the one selected with the profile selection command, unless
+/// its task URL is from another site or can not be interpreted at all.
+pub async fn profile(site: &str) -> R<Option<String>> {
+	if let Ok(manifest) = Manifest::load().await {
+		if let (Some(profile), Some(task_url)) = (manifest.profile, manifest.task_url) {
+			if let Ok((url, _)) = interpret_url(&task_url) {
+				if url.site == site {
+					return Ok(Some(profile));
+				}
+			}
+		}
+	}
+	Ok(ACTIVE_PROFILES.get()?.unwrap_or_default().remove(site))
 }
 
 #[evscode::command(title = "ICIE Password reset")]
 async fn reset() -> R<()> {
 	TELEMETRY.auth_reset.spark();
+	let site = ask_site(
+		"Enter any contest/task URL from the site for which you want to reset the password",
+	)
+	.await?;
+	let profile = profile(&site).await?;
+	Keyring::new("credentials", &site, profile.as_deref()).delete().await;
+	Keyring::new("session", &site, profile.as_deref()).delete().await;
+	Ok(())
+}
+
+#[evscode::command(title = "ICIE Select account profile")]
+async fn select_profile() -> R<()> {
+	TELEMETRY.auth_select_profile.spark();
+	let site = ask_site(
+		"Enter any contest/task URL from the site for which you want to select the account profile",
+	)
+	.await?;
+	let mut all_profiles = PROFILES.get()?.unwrap_or_default();
+	let mut active_profiles = ACTIVE_PROFILES.get()?.unwrap_or_default();
+	let profiles = all_profiles.entry(site.clone()).or_default();
+	let active = active_profiles.get(&site).cloned();
+	let choices =
+		std::iter::once(None).chain(profiles.iter().cloned().map(Some)).collect::<Vec<_>>();
+	let pick = QuickPick::new()
+		.items(choices.iter().enumerate().map(|(index, profile)| {
+			let label = profile.clone().unwrap_or_else(|| "Default".to_owned());
+			let description = if *profile == active { "active" } else { "" };
+			quick_pick::Item::new(index.to_string(), label).description(description.to_owned())
+		}))
+		.item(quick_pick::Item::new("new".to_owned(), "New profile...".to_owned()))
+		.show()
+		.await
+		.ok_or_else(E::cancel)?;
+	let profile = if pick == "new" {
+		let profile = evscode::InputBox::new()
+			.prompt("Name of the new profile, like team or alt")
+			.ignore_focus_out()
+			.show()
+			.await
+			.ok_or_else(E::cancel)?
+			.trim()
+			.to_owned();
+		if profile.is_empty() {
+			return Err(E::error("profile name can not be empty"));
+		}
+		if !profiles.contains(&profile) {
+			profiles.push(profile.clone());
+		}
+		Some(profile)
+	} else {
+		choices[pick.parse::<usize>().unwrap()].clone()
+	};
+	match &profile {
+		Some(profile) => active_profiles.insert(site.clone(), profile.clone()),
+		None => active_profiles.remove(&site),
+	};
+	PROFILES.set(&all_profiles).await;
+	ACTIVE_PROFILES.set(&active_profiles).await;
+	let message = format!("Using the account at {}", fmt_account(&site, profile.as_deref()));
+	evscode::Message::new::<()>(&message).show().await;
+	Ok(())
+}
+
+async fn ask_site(prompt: &str) -> R<String> {
 	let url = evscode::InputBox::new()
-		.prompt("Enter any contest/task URL from the site for which you want to reset the password")
+		.prompt(prompt)
 		.placeholder("https://codeforces.com/contest/.../problem/...")
 		.ignore_focus_out()
 		.show()
 		.await
 		.ok_or_else(E::cancel)?;
-	let site = crate::net::interpret_url(&url)?.0.site;
-	Keyring::new("credentials", &site).delete().await;
-	Keyring::new("session", &site).delete().await;
-	Ok(())
+	Ok(interpret_url(&url)?.0.site)
+}
+
+fn fmt_account(site: &str, profile: Option<&str>) -> String {
+	match profile {
+		Some(profile) => format!("{} ({} profile)", site, profile),
+		None => site.to_owned(),
+	}
 }
 
 async fn help_fix_kwallet() -> R<()> {
@@ -94,14 +189,24 @@ async fn help_fix_kwallet() -> R<()> {
 struct Keyring {
 	kind: &'static str,
 	site: String,
+	profile: Option<String>,
 }
 impl Keyring {
-	fn new(kind: &'static str, site: &str) -> Keyring {
-		Keyring { kind, site: site.to_owned() }
+	fn new(kind: &'static str, site: &str, profile: Option<&str>) -> Keyring {
+		Keyring { kind, site: site.to_owned(), profile: profile.map(String::from) }
+	}
+
+	/// Entries of the default profile do not mention it, so that they stay compatible with the ones
+	/// saved before profiles were added.
+	fn entry(&self) -> String {
+		match &self.profile {
+			Some(profile) => format!("@{} {} {}", self.kind, self.site, profile),
+			None => format!("@{} {}", self.kind, self.site),
+		}
 	}
 
 	async fn get(&self) -> Option<String> {
-		let entry = format!("@{} {}", self.kind, self.site);
+		let entry = self.entry();
 		match JsFuture::from(keytar_sys::get_password("ICIE", &entry)).await {
 			Ok(val) => val.as_string(),
 			Err(e) => {
@@ -112,7 +217,7 @@ impl Keyring {
 	}
 
 	async fn set(&self, value: &str) -> bool {
-		let entry = format!("@{} {}", self.kind, self.site);
+		let entry = self.entry();
 		match JsFuture::from(keytar_sys::set_password("ICIE", &entry, value)).await {
 			Ok(_) => true,
 			Err(e) => {
@@ -123,7 +228,7 @@ impl Keyring {
 	}
 
 	async fn delete(&self) {
-		let entry = format!("@{} {}", self.kind, self.site);
+		let entry = self.entry();
 		match JsFuture::from(keytar_sys::delete_password("ICIE", &entry)).await {
 			Ok(_) => {},
 			Err(e) => {
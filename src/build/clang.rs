@@ -54,6 +54,7 @@ pub async fn compile(
 		.executable
 		.run("", &args, &Environment {
 			time_limit: None,
+			memory_limit: None,
 			// Windows g++ relies on some DLLs that are not in PATH. Since adding stuff to path
 			// would have to be done by the user, it's better to just jest CWD to MinGW binaries
 			// directory. This does not have to be done for compiled executables, because we add the
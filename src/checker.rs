@@ -1,8 +1,9 @@
 use crate::{
-	build::{build, Codegen}, dir, executable::{Environment, Executable}, util::{fs, Tempfile}
+	build::{build, Codegen}, dir, executable::{Environment, Executable, ExitKind}, manifest::{Manifest, ManifestExt}, telemetry::TELEMETRY, test, util::{fs, path::Path, Tempfile}
 };
 use async_trait::async_trait;
-use evscode::R;
+use evscode::{quick_pick, QuickPick, E, R};
+use icie_common::BuiltinChecker;
 use std::{fmt, time::Duration};
 
 /// The maximum time a checker executable can run before getting killed, specified in milliseconds.
@@ -12,22 +13,74 @@ use std::{fmt, time::Duration};
 #[evscode::config]
 static TIME_LIMIT: evscode::Config<Option<u64>> = Some(1500);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, evscode::Configurable)]
+enum Protocol {
+	#[evscode(name = "Exit code")]
+	ExitCode,
+	#[evscode(name = "Testlib")]
+	Testlib,
+}
+
+/// How to interpret the result of a custom checker. With "Exit code", a 0 exit code means the
+/// answer is correct and anything else means it is wrong. With "Testlib", exit codes are
+/// interpreted like testlib does, distinguishing between wrong answers(1), presentation errors(2),
+/// checker failures(3) and partial points(7, or 16 plus the number of points). In both cases, the
+/// checker receives the input, output and answer paths as arguments, which matches testlib's
+/// order, and whatever it prints to stderr is displayed below the output in the test view.
+#[evscode::config]
+static PROTOCOL: evscode::Config<Protocol> = Protocol::ExitCode;
+
 pub async fn get_checker() -> R<Box<dyn Checker+Send+Sync>> {
 	let checker = dir::checker()?;
 	Ok(if !fs::exists(checker.as_ref()).await? {
-		let bx: Box<dyn Checker+Send+Sync> = Box::new(FreeWhitespaceChecker);
-		bx
+		let builtin = Manifest::load().await.ok().and_then(|manifest| manifest.checker);
+		instantiate(&builtin.unwrap_or(BuiltinChecker::FreeWhitespace))
 	} else {
-		let environment =
-			Environment { time_limit: TIME_LIMIT.get().map(Duration::from_millis), cwd: None };
+		let environment = Environment {
+			time_limit: TIME_LIMIT.get().map(Duration::from_millis),
+			memory_limit: None,
+			cwd: None,
+		};
 		let executable = build(checker, Codegen::Release, false).await?;
-		Box::new(ExecChecker { executable, environment })
+		Box::new(ExecChecker { executable, environment, protocol: PROTOCOL.get() })
 	})
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Conclusion {
+	Accepted,
+	WrongAnswer,
+	PresentationError,
+	Failure,
+	Points(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Judgement {
+	pub conclusion: Conclusion,
+	pub comment: Option<String>,
+}
+
+impl Judgement {
+	pub fn accepted_if(correct: bool) -> Judgement {
+		let conclusion = if correct { Conclusion::Accepted } else { Conclusion::WrongAnswer };
+		Judgement { conclusion, comment: None }
+	}
+
+	pub fn success(&self) -> bool {
+		self.conclusion == Conclusion::Accepted
+	}
+}
+
 #[async_trait(?Send)]
 pub trait Checker: fmt::Debug {
-	async fn judge(&self, input: &str, desired: &str, out: &str) -> R<bool>;
+	async fn judge(&self, input: &str, desired: &str, out: &str) -> R<Judgement>;
+
+	/// Whether the checker accepts outputs with tokens different from the desired output, in which
+	/// case highlighting the differing tokens would be misleading.
+	fn tolerates_differences(&self) -> bool {
+		false
+	}
 }
 
 #[derive(Debug)]
@@ -35,8 +88,8 @@ pub struct FreeWhitespaceChecker;
 
 #[async_trait(?Send)]
 impl Checker for FreeWhitespaceChecker {
-	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<bool> {
-		Ok(self.equal_bew(desired, out))
+	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<Judgement> {
+		Ok(Judgement::accepted_if(self.equal_bew(desired, out)))
 	}
 }
 
@@ -74,15 +127,217 @@ impl FreeWhitespaceChecker {
 	}
 }
 
+fn instantiate(builtin: &BuiltinChecker) -> Box<dyn Checker+Send+Sync> {
+	match builtin {
+		BuiltinChecker::FreeWhitespace => Box::new(FreeWhitespaceChecker),
+		BuiltinChecker::Epsilon { absolute, relative } => {
+			Box::new(EpsilonChecker { absolute: *absolute, relative: *relative })
+		},
+		BuiltinChecker::CaseInsensitive => Box::new(CaseInsensitiveChecker),
+		BuiltinChecker::UnorderedLines => Box::new(UnorderedLinesChecker),
+		BuiltinChecker::UnorderedTokens => Box::new(UnorderedTokensChecker),
+	}
+}
+
+#[evscode::command(title = "ICIE Select Checker")]
+async fn select() -> R<()> {
+	TELEMETRY.checker_select.spark();
+	let mut manifest = Manifest::load().await?;
+	let current = manifest.checker.clone().unwrap_or(BuiltinChecker::FreeWhitespace);
+	let kind = QuickPick::new()
+		.ignore_focus_out()
+		.items(vec![
+			quick_pick::Item::new("free_whitespace".to_owned(), "Exact tokens".to_owned())
+				.description("Ignores differences in whitespace".to_owned()),
+			quick_pick::Item::new("epsilon".to_owned(), "Numbers with precision".to_owned())
+				.description("Accepts numbers with a given absolute or relative error".to_owned()),
+			quick_pick::Item::new(
+				"case_insensitive".to_owned(),
+				"Case-insensitive tokens".to_owned(),
+			)
+			.description("Treats YES and yes as equal".to_owned()),
+			quick_pick::Item::new("unordered_lines".to_owned(), "Lines in any order".to_owned())
+				.description("Accepts any permutation of the lines".to_owned()),
+			quick_pick::Item::new("unordered_tokens".to_owned(), "Tokens in any order".to_owned())
+				.description("Accepts any permutation of the tokens".to_owned()),
+		])
+		.show()
+		.await
+		.ok_or_else(E::cancel)?;
+	let checker = match kind.as_str() {
+		"free_whitespace" => BuiltinChecker::FreeWhitespace,
+		"epsilon" => {
+			let default = match current {
+				BuiltinChecker::Epsilon { absolute, .. } => absolute,
+				_ => 1e-6,
+			};
+			let epsilon = evscode::InputBox::new()
+				.prompt("Enter the maximum absolute or relative error")
+				.value(&default.to_string())
+				.ignore_focus_out()
+				.show()
+				.await
+				.ok_or_else(E::cancel)?
+				.trim()
+				.parse::<f64>()
+				.map_err(|_| E::error("the maximum error must be a number"))?;
+			BuiltinChecker::Epsilon { absolute: epsilon, relative: epsilon }
+		},
+		"case_insensitive" => BuiltinChecker::CaseInsensitive,
+		"unordered_lines" => BuiltinChecker::UnorderedLines,
+		"unordered_tokens" => BuiltinChecker::UnorderedTokens,
+		_ => unreachable!(),
+	};
+	manifest.checker = Some(checker);
+	manifest.save(&Path::from_native(evscode::workspace_root()?)).await?;
+	test::view::manage::COLLECTION.update_all().await?;
+	Ok(())
+}
+
+#[derive(Debug)]
+pub struct EpsilonChecker {
+	absolute: f64,
+	relative: f64,
+}
+
+#[async_trait(?Send)]
+impl Checker for EpsilonChecker {
+	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<Judgement> {
+		Ok(Judgement::accepted_if(self.equal_eps(desired, out)))
+	}
+
+	fn tolerates_differences(&self) -> bool {
+		true
+	}
+}
+
+impl EpsilonChecker {
+	fn equal_eps(&self, desired: &str, out: &str) -> bool {
+		let desired = desired.split_whitespace().collect::<Vec<_>>();
+		let out = out.split_whitespace().collect::<Vec<_>>();
+		desired.len() == out.len()
+			&& desired.iter().zip(&out).all(|(desired, out)| {
+				// Identical tokens are compared first, so that "nan" or "inf" are accepted too.
+				desired == out
+					|| match (desired.parse::<f64>(), out.parse::<f64>()) {
+						(Ok(desired), Ok(out)) => {
+							let error = (desired - out).abs();
+							error <= self.absolute || error <= self.relative * desired.abs()
+						},
+						_ => false,
+					}
+			})
+	}
+}
+
+#[test]
+fn test_equal_eps() {
+	let checker = EpsilonChecker { absolute: 1e-6, relative: 1e-6 };
+	assert!(checker.equal_eps("0.5 2", "0.5000001 2.0"));
+	assert!(checker.equal_eps("1000000000", "1000000500"));
+	assert!(!checker.equal_eps("0.5", "0.501"));
+	assert!(!checker.equal_eps("1 2", "1"));
+	assert!(checker.equal_eps("YES 3.0", "YES 3"));
+	assert!(!checker.equal_eps("YES", "NO"));
+	assert!(checker.equal_eps("nan inf", "nan inf"));
+	assert!(!checker.equal_eps("nan", "0"));
+}
+
+#[derive(Debug)]
+pub struct CaseInsensitiveChecker;
+
+#[async_trait(?Send)]
+impl Checker for CaseInsensitiveChecker {
+	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<Judgement> {
+		Ok(Judgement::accepted_if(equal_case_insensitive(desired, out)))
+	}
+
+	fn tolerates_differences(&self) -> bool {
+		true
+	}
+}
+
+fn equal_case_insensitive(desired: &str, out: &str) -> bool {
+	let desired = desired.split_whitespace().map(str::to_lowercase);
+	let out = out.split_whitespace().map(str::to_lowercase);
+	desired.eq(out)
+}
+
+#[test]
+fn test_equal_case_insensitive() {
+	assert!(equal_case_insensitive("YES\nNo", "yes no"));
+	assert!(equal_case_insensitive("Ünïcode", "üNÏCODE"));
+	assert!(!equal_case_insensitive("yes", "yes yes"));
+	assert!(!equal_case_insensitive("yes no", "no yes"));
+}
+
+#[derive(Debug)]
+pub struct UnorderedLinesChecker;
+
+#[async_trait(?Send)]
+impl Checker for UnorderedLinesChecker {
+	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<Judgement> {
+		Ok(Judgement::accepted_if(sorted_lines(desired) == sorted_lines(out)))
+	}
+
+	fn tolerates_differences(&self) -> bool {
+		true
+	}
+}
+
+fn sorted_lines(text: &str) -> Vec<String> {
+	let mut lines = text
+		.lines()
+		.map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>();
+	lines.sort();
+	lines
+}
+
+#[test]
+fn test_sorted_lines() {
+	assert_eq!(sorted_lines("1 2\n3  4 \n\n0\n"), sorted_lines("0\n3 4\n1   2"));
+	assert_ne!(sorted_lines("1 2\n3 4"), sorted_lines("1 3\n2 4"));
+}
+
+#[derive(Debug)]
+pub struct UnorderedTokensChecker;
+
+#[async_trait(?Send)]
+impl Checker for UnorderedTokensChecker {
+	async fn judge(&self, _input: &str, desired: &str, out: &str) -> R<Judgement> {
+		Ok(Judgement::accepted_if(sorted_tokens(desired) == sorted_tokens(out)))
+	}
+
+	fn tolerates_differences(&self) -> bool {
+		true
+	}
+}
+
+fn sorted_tokens(text: &str) -> Vec<&str> {
+	let mut tokens = text.split_whitespace().collect::<Vec<_>>();
+	tokens.sort();
+	tokens
+}
+
+#[test]
+fn test_sorted_tokens() {
+	assert_eq!(sorted_tokens("3 1\n2  "), sorted_tokens("1 2 3"));
+	assert_ne!(sorted_tokens("1 1 2"), sorted_tokens("1 2 2"));
+	assert_ne!(sorted_tokens("1 2"), sorted_tokens("1 2 3"));
+}
+
 #[derive(Debug)]
 pub struct ExecChecker {
 	pub executable: Executable,
 	pub environment: Environment,
+	protocol: Protocol,
 }
 
 #[async_trait(?Send)]
 impl Checker for ExecChecker {
-	async fn judge(&self, input: &str, desired: &str, out: &str) -> R<bool> {
+	async fn judge(&self, input: &str, desired: &str, out: &str) -> R<Judgement> {
 		let input_file = Tempfile::new("input.in", input).await?;
 		let desired_file = Tempfile::new("desired.out", desired).await?;
 		let out_file = Tempfile::new("output.out", out).await?;
@@ -92,6 +347,57 @@ impl Checker for ExecChecker {
 			desired_file.path().to_str().unwrap(),
 		];
 		let run = self.executable.run("", &args, &self.environment).await?;
-		Ok(run.success())
+		let comment = Some(run.stderr.trim()).filter(|comment| !comment.is_empty());
+		Ok(match self.protocol {
+			Protocol::ExitCode => Judgement {
+				comment: comment.map(str::to_owned),
+				..Judgement::accepted_if(run.success())
+			},
+			Protocol::Testlib if run.exit_kind != ExitKind::Normal => {
+				Judgement { conclusion: Conclusion::Failure, comment: comment.map(str::to_owned) }
+			},
+			Protocol::Testlib => parse_testlib(run.exit_code, comment),
+		})
 	}
+
+	/// Custom checkers are usually written for tasks with many correct answers.
+	fn tolerates_differences(&self) -> bool {
+		true
+	}
+}
+
+fn parse_testlib(exit_code: Option<i32>, comment: Option<&str>) -> Judgement {
+	let conclusion = match exit_code {
+		Some(0) => Conclusion::Accepted,
+		Some(1) => Conclusion::WrongAnswer,
+		Some(2) => Conclusion::PresentationError,
+		Some(7) => match comment.and_then(parse_testlib_points) {
+			Some(points) => Conclusion::Points(points),
+			None => Conclusion::Failure,
+		},
+		Some(code) if code >= 16 => Conclusion::Points(f64::from(code - 16)),
+		_ => Conclusion::Failure,
+	};
+	Judgement { conclusion, comment: comment.map(str::to_owned) }
+}
+
+fn parse_testlib_points(comment: &str) -> Option<f64> {
+	let comment = comment.trim_start_matches("points").trim_start();
+	comment.split_whitespace().next()?.parse().ok()
+}
+
+#[test]
+fn test_parse_testlib() {
+	assert_eq!(parse_testlib(Some(0), Some("ok 3 numbers")), Judgement {
+		conclusion: Conclusion::Accepted,
+		comment: Some("ok 3 numbers".to_owned())
+	});
+	assert_eq!(parse_testlib(Some(1), None).conclusion, Conclusion::WrongAnswer);
+	assert_eq!(parse_testlib(Some(2), None).conclusion, Conclusion::PresentationError);
+	assert_eq!(parse_testlib(Some(3), None).conclusion, Conclusion::Failure);
+	let partial = parse_testlib(Some(7), Some("points 12.5 almost"));
+	assert_eq!(partial.conclusion, Conclusion::Points(12.5));
+	assert_eq!(parse_testlib(Some(7), Some("garbage")).conclusion, Conclusion::Failure);
+	assert_eq!(parse_testlib(Some(16 + 40), None).conclusion, Conclusion::Points(40.0));
+	assert_eq!(parse_testlib(None, None).conclusion, Conclusion::Failure);
 }
@@ -33,6 +33,16 @@ static GEN_STEM: evscode::Config<String> = "gen";
 #[evscode::config]
 static CHECKER_STEM: evscode::Config<String> = "checker";
 
+/// File stem of the task interactor source file. For interactive tasks, this is the program which
+/// will talk with your solution - everything it writes to stdout will be passed to the solution's
+/// stdin and vice versa. The interactor will receive the path to the test input as its first
+/// argument, so declare main as `int main(int, char* argv[])` and open it with `ifstream
+/// in(argv[1])`. The test passes if the interactor returns a 0 exit code and fails otherwise. For
+/// example, if this is set to "interactor", the source will be called "interactor.cpp"(assuming
+/// icie.dir.cppExtension is set to "cpp").
+#[evscode::config]
+static INTERACTOR_STEM: evscode::Config<String> = "interactor";
+
 /// The file extension used for sources written in the C++ language.
 #[evscode::config]
 pub static CPP_EXTENSION: evscode::Config<String> = "cpp";
@@ -78,6 +88,12 @@ pub fn checker() -> evscode::R<Path> {
 		.with_extension(&*CPP_EXTENSION.get()))
 }
 
+pub fn interactor() -> evscode::R<Path> {
+	Ok(Path::from_native(evscode::workspace_root()?)
+		.join(&*INTERACTOR_STEM.get())
+		.with_extension(&*CPP_EXTENSION.get()))
+}
+
 pub fn tests() -> evscode::R<Path> {
 	Ok(Path::from_native(evscode::workspace_root()?).join(&*TESTS_DIRECTORY.get()))
 }
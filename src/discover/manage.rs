@@ -1,6 +1,6 @@
 use crate::{
 	build::{build, Codegen}, checker::get_checker, dir, discover::render::render, executable::{Environment, Executable}, test::{
-		self, add_test, judge::{simple_test, Outcome, Verdict}, time_limit, Task
+		self, add_test, io_files, judge::{simple_test, Outcome, Verdict}, memory_limit, time_limit, Task
 	}
 };
 use async_trait::async_trait;
@@ -50,9 +50,20 @@ impl Behaviour for Discover {
 		let solution = build(&source, Codegen::Debug, false).await?;
 		let brut = build(dir::brut()?, Codegen::Release, false).await?;
 		let gen = build(dir::gen()?, Codegen::Release, false).await?;
+		// Only the solution uses the I/O files, brute force solutions and generators always use
+		// stdin and stdout.
+		let (input_file, output_file) = io_files().await;
 		let task = Task {
 			checker: get_checker().await?,
-			environment: Environment { time_limit: time_limit(), cwd: None },
+			// Brut would have to be interactive too, so interactors are not supported here.
+			interactor: None,
+			environment: Environment {
+				time_limit: time_limit().await,
+				memory_limit: memory_limit().await,
+				cwd: None,
+			},
+			input_file,
+			output_file,
 		};
 		let mut best_row: Option<Row> = None;
 		let mut events = Box::pin(cancel_on(
@@ -171,8 +182,12 @@ where S: Serializer {
 	s.serialize_str(match verdict {
 		Verdict::Accepted { .. } => "accept",
 		Verdict::WrongAnswer => "wrong_answer",
+		Verdict::PresentationError => "presentation_error",
+		Verdict::Scored { .. } => "scored",
+		Verdict::CheckerFailure => "checker_failure",
 		Verdict::RuntimeError => "runtime_error",
 		Verdict::TimeLimitExceeded => "time_limit_exceeded",
+		Verdict::MemoryLimitExceeded => "memory_limit_exceeded",
 		Verdict::IgnoredNoOut => "ignored_no_out",
 	})
 }
@@ -42,10 +42,18 @@ function pretty_outcome(outcome) {
 		return 'Accept';
 	} else if (outcome === 'wrong_answer') {
 		return 'Wrong answer';
+	} else if (outcome === 'presentation_error') {
+		return 'Presentation error';
+	} else if (outcome === 'scored') {
+		return 'Partially scored';
+	} else if (outcome === 'checker_failure') {
+		return 'Checker failure';
 	} else if (outcome === 'runtime_error') {
 		return 'Runtime error';
 	} else if (outcome === 'time_limit_exceeded') {
 		return 'Time limit exceeded';
+	} else if (outcome === 'memory_limit_exceeded') {
+		return 'Memory limit exceeded';
 	} else if (outcome === 'ignored_no_out') {
 		return 'Ignored because of no out';
 	} else {
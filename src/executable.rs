@@ -1,11 +1,11 @@
-use crate::util::{node_hrtime, path::Path, sleep};
+use crate::util::{fs, node_hrtime, path::Path, sleep, OS};
 use evscode::{E, R};
 use futures::{
-	channel::{mpsc, oneshot}, future::join3, FutureExt, StreamExt
+	channel::{mpsc, oneshot}, future::{join, join3}, FutureExt, StreamExt
 };
-use node_sys::child_process::Stdio;
+use node_sys::child_process::{ChildProcess, Stdio};
 use std::{
-	future::Future, sync::atomic::{AtomicBool, Ordering::SeqCst}, time::Duration
+	cell::RefCell, future::Future, sync::atomic::{AtomicBool, AtomicU64, Ordering::SeqCst}, time::Duration
 };
 use wasm_bindgen::{closure::Closure, JsCast, JsValue, __rt::core::pin::Pin};
 
@@ -13,6 +13,7 @@ use wasm_bindgen::{closure::Closure, JsCast, JsValue, __rt::core::pin::Pin};
 pub enum ExitKind {
 	Normal,
 	TimeLimitExceeded,
+	MemoryLimitExceeded,
 }
 
 #[derive(Debug)]
@@ -22,6 +23,8 @@ pub struct Run {
 	pub exit_code: Option<i32>,
 	pub exit_kind: ExitKind,
 	pub time: Duration,
+	/// Peak resident set size in bytes, only measured on Linux.
+	pub memory: Option<u64>,
 }
 impl Run {
 	pub fn success(&self) -> bool {
@@ -32,6 +35,8 @@ impl Run {
 #[derive(Debug)]
 pub struct Environment {
 	pub time_limit: Option<Duration>,
+	/// Memory limit in bytes, only enforced on Linux.
+	pub memory_limit: Option<u64>,
 	pub cwd: Option<Path>,
 }
 
@@ -50,12 +55,95 @@ impl Executable {
 	}
 
 	pub async fn run(&self, input: &str, args: &[&str], environment: &Environment) -> R<Run> {
+		let input_buffer =
+			node_sys::buffer::Buffer::from(js_sys::Uint8Array::from(input.as_bytes()));
+		let kid = self.spawn(args, environment).await?;
+		let t1 = node_hrtime();
+		kid.stdin().unwrap().end(&input_buffer, (), Closure::once_into_js(|| {}));
+		let capture_stdout = capture_node_stream(kid.stdout().unwrap());
+		let capture_stderr = capture_node_stream(kid.stderr().unwrap());
+		let drive_exec = supervise(&kid, environment);
+		let (exit, stdout, stderr) = join3(drive_exec, capture_stdout, capture_stderr).await;
+		let stdout = String::from_utf8_lossy(&stdout).into_owned();
+		let stderr = String::from_utf8_lossy(&stderr).into_owned();
+		Ok(exit.into_run(stdout, stderr, t1))
+	}
+
+	/// Runs the executable with its stdin and stdout connected to the interactor's stdout and stdin
+	/// respectively, capturing everything the two programs send to each other.
+	pub async fn run_interactive(
+		&self,
+		interactor: &Executable,
+		interactor_args: &[&str],
+		environment: &Environment,
+		interactor_environment: &Environment,
+	) -> R<Interaction>
+	{
+		let kid = self.spawn(&[], environment).await?;
+		let t1 = node_hrtime();
+		let interactor_kid = match interactor.spawn(interactor_args, interactor_environment).await {
+			Ok(interactor_kid) => interactor_kid,
+			Err(e) => {
+				kid.kill(9);
+				return Err(e.context("running interactor executable failed"));
+			},
+		};
+		let interactor_t1 = node_hrtime();
+		let transcript = RefCell::new(Vec::new());
+		let forward_queries = forward_node_stream(
+			kid.stdout().unwrap(),
+			interactor_kid.stdin().unwrap(),
+			Party::Solution,
+			&transcript,
+		);
+		let forward_responses = forward_node_stream(
+			interactor_kid.stdout().unwrap(),
+			kid.stdin().unwrap(),
+			Party::Interactor,
+			&transcript,
+		);
+		let capture_stderr = capture_node_stream(kid.stderr().unwrap());
+		let capture_interactor_stderr = capture_node_stream(interactor_kid.stderr().unwrap());
+		let drive_exec = supervise(&kid, environment);
+		let drive_interactor = supervise(&interactor_kid, interactor_environment);
+		let ((exit, interactor_exit), _, (stderr, interactor_stderr)) = join3(
+			join(drive_exec, drive_interactor),
+			join(forward_queries, forward_responses),
+			join(capture_stderr, capture_interactor_stderr),
+		)
+		.await;
+		// Chunks are only decoded after being joined, because multibyte characters can be split
+		// between them.
+		let transcript = transcript
+			.into_inner()
+			.into_iter()
+			.map(|(sender, data)| Message {
+				sender,
+				data: String::from_utf8_lossy(&data).into_owned(),
+			})
+			.collect::<Vec<_>>();
+		let collect_sent = |party: Party| {
+			transcript
+				.iter()
+				.filter(|message| message.sender == party)
+				.map(|message| message.data.as_str())
+				.collect::<String>()
+		};
+		let stdout = collect_sent(Party::Solution);
+		let interactor_stdout = collect_sent(Party::Interactor);
+		let stderr = String::from_utf8_lossy(&stderr).into_owned();
+		let interactor_stderr = String::from_utf8_lossy(&interactor_stderr).into_owned();
+		let solution = exit.into_run(stdout, stderr, t1);
+		let interactor =
+			interactor_exit.into_run(interactor_stdout, interactor_stderr, interactor_t1);
+		Ok(Interaction { solution, interactor, transcript })
+	}
+
+	async fn spawn(&self, args: &[&str], environment: &Environment) -> R<ChildProcess> {
 		let js_args = js_sys::Array::new();
 		for arg in args {
 			js_args.push(&JsValue::from_str(arg));
 		}
-		let input_buffer =
-			node_sys::buffer::Buffer::from(js_sys::Uint8Array::from(input.as_bytes()));
 		let cwd = environment
 			.cwd
 			.clone()
@@ -75,7 +163,6 @@ impl Executable {
 				windows_hide: None,
 			},
 		);
-		let t1 = node_hrtime();
 		// This is not the proper way to check whether an error has happened, but doing otherwise
 		// would be ugly. Blame Node for not making a proper asynchronous spawn or throwing an
 		// exception.
@@ -84,34 +171,135 @@ impl Executable {
 			kid.on_2("error", &Closure::once_into_js(|err: js_sys::Error| tx.send(err).unwrap()));
 			return Err(E::from(rx.await.unwrap()).context("running solution executable failed"));
 		}
-		kid.stdin().unwrap().end(&input_buffer, (), Closure::once_into_js(|| {}));
-		let capture_stdout = capture_node_stream(kid.stdout().unwrap());
-		let capture_stderr = capture_node_stream(kid.stderr().unwrap());
-		let execution_finished = AtomicBool::new(false);
-		let timed_out = AtomicBool::new(false);
-		let drive_exec = async {
-			let exit_code = wait_process(&kid).await;
-			let t2 = node_hrtime();
-			execution_finished.store(true, SeqCst);
-			(exit_code, t2)
+		Ok(kid)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Party {
+	Solution,
+	Interactor,
+}
+
+#[derive(Debug)]
+pub struct Message {
+	pub sender: Party,
+	pub data: String,
+}
+
+#[derive(Debug)]
+pub struct Interaction {
+	pub solution: Run,
+	pub interactor: Run,
+	pub transcript: Vec<Message>,
+}
+
+struct Exit {
+	exit_code: Option<i32>,
+	exit_kind: ExitKind,
+	t2: Duration,
+	memory: Option<u64>,
+}
+impl Exit {
+	fn into_run(self, stdout: String, stderr: String, t1: Duration) -> Run {
+		let Exit { exit_code, exit_kind, t2, memory } = self;
+		Run { stdout, stderr, exit_code, exit_kind, time: t2 - t1, memory }
+	}
+}
+
+async fn supervise(kid: &ChildProcess, environment: &Environment) -> Exit {
+	let execution_finished = AtomicBool::new(false);
+	let timed_out = AtomicBool::new(false);
+	let memory_exceeded = AtomicBool::new(false);
+	let peak_memory = AtomicU64::new(0);
+	let drive_exec = async {
+		let exit_code = wait_process(kid).await;
+		let t2 = node_hrtime();
+		execution_finished.store(true, SeqCst);
+		(exit_code, t2)
+	};
+	let drive_exec = soft_timeout(drive_exec, environment.time_limit, || {
+		if !execution_finished.load(SeqCst) {
+			timed_out.store(true, SeqCst);
+			kid.kill(9);
+		}
+	});
+	let drive_exec = with_background(
+		drive_exec,
+		watch_memory(
+			kid,
+			environment.memory_limit,
+			&execution_finished,
+			&memory_exceeded,
+			&peak_memory,
+		),
+	);
+	let (exit_code, t2) = drive_exec.await;
+	let exit_kind = if memory_exceeded.load(SeqCst) {
+		ExitKind::MemoryLimitExceeded
+	} else if timed_out.load(SeqCst) {
+		ExitKind::TimeLimitExceeded
+	} else {
+		ExitKind::Normal
+	};
+	let memory = match peak_memory.load(SeqCst) {
+		0 => None,
+		peak => Some(peak),
+	};
+	Exit { exit_code, exit_kind, t2, memory }
+}
+
+/// How often the memory usage of a running process is sampled. Node does not expose rusage of its
+/// child processes, so on Linux it is read from procfs instead.
+const MEMORY_POLL_DELAY: Duration = Duration::from_millis(10);
+
+async fn watch_memory(
+	kid: &ChildProcess,
+	memory_limit: Option<u64>,
+	execution_finished: &AtomicBool,
+	memory_exceeded: &AtomicBool,
+	peak_memory: &AtomicU64,
+)
+{
+	let pid = match (OS::query(), kid.pid()) {
+		(Ok(OS::Linux), Some(pid)) => pid,
+		_ => return,
+	};
+	let status_path = Path::from_native(format!("/proc/{}/status", pid));
+	while !execution_finished.load(SeqCst) {
+		// The process could have been reaped in the meantime, in which case the last sample is the
+		// best estimate available.
+		let status = match fs::read_to_string(&status_path).await {
+			Ok(status) => status,
+			Err(_) => break,
 		};
-		let drive_exec = soft_timeout(drive_exec, environment.time_limit, || {
-			if !execution_finished.load(SeqCst) {
-				timed_out.store(true, SeqCst);
+		if let Some(peak) = parse_peak_memory(&status) {
+			peak_memory.store(peak, SeqCst);
+			if memory_limit.map_or(false, |limit| peak > limit) && !execution_finished.load(SeqCst)
+			{
+				memory_exceeded.store(true, SeqCst);
 				kid.kill(9);
+				break;
 			}
-		});
-		let ((exit_code, t2), stdout, stderr) =
-			join3(drive_exec, capture_stdout, capture_stderr).await;
-		let exit_kind =
-			if timed_out.load(SeqCst) { ExitKind::TimeLimitExceeded } else { ExitKind::Normal };
-		let stdout = String::from_utf8_lossy(&stdout).into_owned();
-		let stderr = String::from_utf8_lossy(&stderr).into_owned();
-		Ok(Run { stdout, stderr, exit_code, exit_kind, time: t2 - t1 })
+		}
+		sleep(MEMORY_POLL_DELAY).await;
 	}
 }
 
-async fn wait_process(kid: &node_sys::child_process::ChildProcess) -> Option<i32> {
+fn parse_peak_memory(status: &str) -> Option<u64> {
+	let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+	let kilobytes = line["VmHWM:".len()..].trim().trim_end_matches("kB").trim();
+	Some(kilobytes.parse::<u64>().ok()? * 1024)
+}
+
+#[test]
+fn test_parse_peak_memory() {
+	let status = "Name:\tmain\nVmPeak:\t   14212 kB\nVmHWM:\t    2860 kB\nVmRSS:\t    2860 kB\n";
+	assert_eq!(parse_peak_memory(status), Some(2860 * 1024));
+	assert_eq!(parse_peak_memory("Name:\tmain\n"), None);
+}
+
+async fn wait_process(kid: &ChildProcess) -> Option<i32> {
 	let (tx, rx) = oneshot::channel();
 	let mut tx = Some(tx);
 	kid.on_2(
@@ -124,6 +312,41 @@ async fn wait_process(kid: &node_sys::child_process::ChildProcess) -> Option<i32
 }
 
 async fn capture_node_stream(readable: node_sys::stream::Readable) -> Vec<u8> {
+	let mut buf = Vec::new();
+	consume_node_stream(readable, |chunk| buf.extend_from_slice(&chunk)).await;
+	buf
+}
+
+async fn forward_node_stream(
+	readable: node_sys::stream::Readable,
+	writable: node_sys::stream::Writable,
+	sender: Party,
+	transcript: &RefCell<Vec<(Party, Vec<u8>)>>,
+)
+{
+	// Writing to a process that has already exited emits an EPIPE error, which would crash the
+	// extension host if left unhandled.
+	let error_handler = Closure::wrap(Box::new(|_: JsValue| {}) as Box<dyn FnMut(JsValue)>);
+	writable.on_1("error", &error_handler.into_js_value());
+	consume_node_stream(readable, |chunk| {
+		let buffer = node_sys::buffer::Buffer::from(js_sys::Uint8Array::from(chunk.as_slice()));
+		writable.write(&buffer, (), Closure::once_into_js(|| {}));
+		let mut transcript = transcript.borrow_mut();
+		match transcript.last_mut() {
+			Some((last_sender, data)) if *last_sender == sender => data.extend_from_slice(&chunk),
+			_ => transcript.push((sender, chunk)),
+		}
+	})
+	.await;
+	let empty = node_sys::buffer::Buffer::from(js_sys::Uint8Array::new_with_length(0));
+	writable.end(&empty, (), Closure::once_into_js(|| {}));
+}
+
+pub async fn consume_node_stream(
+	readable: node_sys::stream::Readable,
+	mut on_chunk: impl FnMut(Vec<u8>),
+)
+{
 	let (tx, mut rx) = mpsc::unbounded();
 	let tx2 = tx.clone();
 	let end_handler = Closure::wrap(Box::new(move || {
@@ -140,11 +363,20 @@ async fn capture_node_stream(readable: node_sys::stream::Readable) -> Vec<u8> {
 		}
 	}) as Box<dyn FnMut()>);
 	readable2.on_0("readable", &readable_handler);
-	let mut buf = Vec::new();
 	while let Some(Some(chunk)) = rx.next().await {
-		buf.extend_from_slice(&chunk);
+		on_chunk(chunk);
+	}
+}
+
+async fn with_background<X>(task: impl Future<Output=X>, background: impl Future<Output=()>) -> X {
+	let mut task = Box::pin(task).fuse();
+	let mut background = Box::pin(background).fuse();
+	loop {
+		futures::select! {
+			x = task => break x,
+			() = background => (),
+		}
 	}
-	buf
 }
 
 async fn soft_timeout<X>(
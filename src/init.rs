@@ -7,6 +7,7 @@ use unijudge::{
 	boxed::{BoxedContestURL, BoxedTaskURL}, chrono::Local, Backend, Resource, TaskDetails, URL
 };
 
+pub mod companion;
 pub mod contest;
 mod files;
 pub mod names;
@@ -30,7 +31,10 @@ async fn scan() -> R<()> {
 			} else {
 				format!("{} {}", site_prefix, contest.title)
 			};
-			let start = contest.start.with_timezone(&Local).to_rfc2822();
+			let start = match contest.start {
+				Some(start) => start.with_timezone(&Local).to_rfc2822(),
+				None => "Started".to_owned(),
+			};
 			quick_pick::Item::new(index.to_string(), label).description(start)
 		}))
 		.match_on_description()
@@ -146,8 +150,7 @@ async fn init_task(root: &Path, url: Option<String>, meta: Option<TaskDetails>)
 		.and_then(|meta| meta.examples.as_ref())
 		.map(|examples| examples.as_slice())
 		.unwrap_or(&[]);
-	let statement = meta.as_ref().and_then(|meta| meta.statement.clone());
-	files::init_manifest(root, &url, statement).await?;
+	files::init_manifest(root, &url, meta.as_ref()).await?;
 	files::init_template(root).await?;
 	files::init_examples(root, examples).await?;
 	Ok(())
@@ -0,0 +1,211 @@
+use crate::{
+	dir, executable::consume_node_stream, init::{init_task, names}, telemetry::TELEMETRY, util::fs
+};
+use evscode::{E, R};
+use node_sys::http::{IncomingMessage, ServerResponse};
+use serde::Deserialize;
+use std::time::Duration;
+use unijudge::{Example, TaskDetails};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// The port on which tasks sent by the Competitive Companion browser extension are received. Add
+/// it to the custom ports in the extension settings, unless it is already one of the defaults.
+/// Only one VS Code window can receive tasks at a time. Leave empty to disable receiving tasks.
+#[evscode::config]
+static PORT: evscode::Config<Option<u64>> = Some(27121);
+
+/// Task description in the Competitive Companion format, see [its documentation][1].
+/// [1]: https://github.com/jmerle/competitive-companion#explanation
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Problem {
+	name: String,
+	group: String,
+	url: String,
+	#[serde(default)]
+	interactive: bool,
+	/// Memory limit in megabytes.
+	memory_limit: u64,
+	/// Time limit in milliseconds.
+	time_limit: u64,
+	tests: Vec<Test>,
+	input: Stream,
+	output: Stream,
+	batch: Batch,
+}
+
+#[derive(Debug, Deserialize)]
+struct Test {
+	input: String,
+	output: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stream {
+	#[serde(rename = "type")]
+	kind: String,
+	#[serde(default)]
+	file_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Batch {
+	size: usize,
+}
+
+pub fn listen() {
+	let port = match PORT.get() {
+		Some(port) => port as u16,
+		None => return,
+	};
+	let request_handler =
+		Closure::wrap(Box::new(|request: IncomingMessage, response: ServerResponse| {
+			evscode::spawn(receive(request, response));
+		}) as Box<dyn FnMut(IncomingMessage, ServerResponse)>);
+	let error_handler = Closure::wrap(Box::new(move |error: JsValue| {
+		let error = error.unchecked_into::<js_sys::Error>();
+		let code = js_sys::Reflect::get(&error, &JsValue::from_str("code")).ok();
+		// This happens when another VS Code window is already receiving the tasks.
+		let e = if code.and_then(|code| code.as_string()).as_deref() == Some("EADDRINUSE") {
+			E::error(format!("port {} is already in use, probably by another VS Code window", port))
+		} else {
+			E::from(error)
+		};
+		e.context("cannot receive tasks from Competitive Companion").warning().emit();
+	}) as Box<dyn FnMut(JsValue)>);
+	let server = node_sys::http::create_server(request_handler.as_ref());
+	server.on_1("error", error_handler.as_ref());
+	server.listen(port, "127.0.0.1");
+	request_handler.forget();
+	error_handler.forget();
+}
+
+async fn receive(request: IncomingMessage, response: ServerResponse) -> R<()> {
+	if request.method() != "POST" {
+		response.write_head(405);
+		response.end();
+		return Ok(());
+	}
+	// Web pages can only send JSON to other origins after a CORS preflight request, which is never
+	// answered here, so this stops arbitrary pages from creating projects.
+	if !is_json(&request) {
+		response.write_head(415);
+		response.end();
+		return Ok(());
+	}
+	let mut body = Vec::new();
+	consume_node_stream(request.unchecked_into(), |chunk| body.extend(chunk)).await;
+	let problem = match serde_json::from_slice::<Problem>(&body) {
+		Ok(problem) => problem,
+		Err(e) => {
+			response.write_head(400);
+			response.end();
+			return Err(E::from_std(e)
+				.context("Competitive Companion sent a task in an unrecognized format"));
+		},
+	};
+	response.write_head(200);
+	response.end();
+	TELEMETRY.init_companion.spark();
+	import(problem).await
+}
+
+fn is_json(request: &IncomingMessage) -> bool {
+	js_sys::Reflect::get(&request.headers(), &JsValue::from_str("content-type"))
+		.ok()
+		.and_then(|content_type| content_type.as_string())
+		.map_or(false, |content_type| {
+			content_type.split(';').next().unwrap().trim().eq_ignore_ascii_case("application/json")
+		})
+}
+
+async fn import(problem: Problem) -> R<()> {
+	let _status = crate::STATUS.push("Importing");
+	let url = problem.url.clone();
+	let batch_size = problem.batch.size;
+	let meta = task_details(problem);
+	let title = meta.title.clone();
+	let root = dir::PROJECT_DIRECTORY.get();
+	let root = names::design_task_name(root.as_ref(), Some(&meta)).await?;
+	fs::create_dir_all(root.as_ref()).await?;
+	init_task(root.as_ref(), Some(url), Some(meta)).await?;
+	drop(_status);
+	// When a whole contest is sent, opening every task would just switch between them.
+	if batch_size == 1
+		|| evscode::Message::new(&format!("Imported {}", title))
+			.item((), "Open", false)
+			.show()
+			.await
+			.is_some()
+	{
+		evscode::open_folder(root.to_str().unwrap(), false).await;
+	}
+	Ok(())
+}
+
+fn task_details(problem: Problem) -> TaskDetails {
+	let (symbol, title) = split_name(&problem.name);
+	let (site, contest) = split_group(&problem.group);
+	TaskDetails {
+		id: symbol.unwrap_or_else(|| title.clone()),
+		title,
+		contest_id: contest,
+		site_short: site,
+		examples: Some(
+			problem
+				.tests
+				.into_iter()
+				.map(|test| Example { input: test.input, output: test.output })
+				.collect(),
+		),
+		statement: None,
+		url: problem.url,
+		time_limit: Some(Duration::from_millis(problem.time_limit)),
+		memory_limit: Some(problem.memory_limit * 1024 * 1024),
+		input_file: stream_file(problem.input),
+		output_file: stream_file(problem.output),
+		interactive: problem.interactive,
+	}
+}
+
+fn stream_file(stream: Stream) -> Option<String> {
+	if stream.kind == "file" { stream.file_name } else { None }
+}
+
+/// Splits names like "A. Diverse Strings" or "B - Happy Birthday!" into the task symbol and title.
+fn split_name(name: &str) -> (Option<String>, String) {
+	for separator in &[". ", " - "] {
+		if let Some(i) = name.find(separator) {
+			let symbol = &name[..i];
+			if symbol.len() <= 3 && symbol.chars().all(char::is_alphanumeric) {
+				return (Some(symbol.to_owned()), name[i + separator.len()..].trim().to_owned());
+			}
+		}
+	}
+	(None, name.trim().to_owned())
+}
+
+/// Splits groups like "Codeforces - Educational Round 80" into the site name and contest title.
+fn split_group(group: &str) -> (String, String) {
+	let (site, contest) = match group.find(" - ") {
+		Some(i) => (&group[..i], &group[i + 3..]),
+		None => (group, group),
+	};
+	let site = site.split_whitespace().next().unwrap_or("unknown").to_lowercase();
+	(site, contest.trim().to_owned())
+}
+
+#[test]
+fn test_split() {
+	let codeforces = split_name("A. Diverse Strings");
+	assert_eq!(codeforces, (Some("A".to_owned()), "Diverse Strings".to_owned()));
+	let atcoder = split_name("B - Happy Birthday!");
+	assert_eq!(atcoder, (Some("B".to_owned()), "Happy Birthday!".to_owned()));
+	assert_eq!(split_name("Two Sum - Hard"), (None, "Two Sum - Hard".to_owned()));
+	assert_eq!(
+		split_group("Codeforces - Educational Round 80"),
+		("codeforces".to_owned(), "Educational Round 80".to_owned())
+	);
+	assert_eq!(split_group("Kattis"), ("kattis".to_owned(), "Kattis".to_owned()));
+}
@@ -43,7 +43,7 @@ pub async fn sprint(
 	let task0 = tasks.get(0).wrap("could not find any tasks in contest")?;
 	let task0_name = format!("1/{}", tasks.len());
 	let task0_details = fetch_task(task0, &task0_name, &sess).await?;
-	let task0_url = sess.run(|backend, sess| async move { backend.task_url(sess, task0) }).await?;
+	let task0_url = sess.task_url(task0)?;
 	let task0_path = design_task_name(root_dir.as_ref(), Some(&task0_details)).await?;
 	init_task(task0_path.as_ref(), Some(task0_url), Some(task0_details)).await?;
 	let manifest = Manifest { contest_url: url_raw };
@@ -87,7 +87,7 @@ async fn inner_sprint(manifest: &Path) -> R<()> {
 			let root = design_task_name(contest_dir.as_ref(), Some(&details)).await?;
 			init_task(
 				root.as_ref(),
-				Some(sess.run(|_, _| async { sess.backend.task_url(&sess.session, &task) }).await?),
+				Some(sess.task_url(&task)?),
 				Some(details),
 			)
 			.await?;
@@ -115,7 +115,10 @@ async fn wait_for_contest(url: &str, site: &str, sess: &Arc<Session>) -> R<()> {
 		Some(details) => details,
 		None => return Ok(()),
 	};
-	let deadline = SystemTime::from(details.start);
+	let deadline = match details.start {
+		Some(start) => SystemTime::from(start),
+		None => return Ok(()),
+	};
 	let total = match deadline.duration_since(time_now()) {
 		Ok(total) => total,
 		Err(_) => return Ok(()),
@@ -187,7 +190,7 @@ fn spawn_login_suggestion(site: &str, sess: &Arc<Session>) {
 	let site = site.to_owned();
 	let sess = sess.clone();
 	evscode::spawn(async move {
-		if !auth::has_any_saved(&site).await {
+		if !auth::has_any_saved(&site, sess.profile.as_deref()).await {
 			let message = format!(
 				"You are not logged in to {}, maybe do it now to save time when submitting?",
 				site
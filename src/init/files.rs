@@ -1,17 +1,11 @@
 use crate::{
-	dir, init::SOLUTION_TEMPLATE, util::{fs, path::Path}
+	dir, init::SOLUTION_TEMPLATE, manifest::{Manifest, ManifestExt}, util::{fs, path::Path}
 };
 use evscode::{error::ResultExt, R};
-use unijudge::{Example, Statement};
+use unijudge::{Example, TaskDetails};
 
-pub async fn init_manifest(
-	root: &Path,
-	url: &Option<String>,
-	statement: Option<Statement>,
-) -> R<()>
-{
-	let manifest = crate::manifest::Manifest { task_url: url.clone(), statement };
-	manifest.save(root).await?;
+pub async fn init_manifest(root: &Path, url: &Option<String>, meta: Option<&TaskDetails>) -> R<()> {
+	Manifest::new(url.clone(), meta).save(root).await?;
 	Ok(())
 }
 
@@ -7,6 +7,9 @@ use unijudge::{boxed::BoxedContestDetails, Backend};
 pub async fn fetch_contests() -> Vec<(Arc<net::Session>, BoxedContestDetails, &'static BackendMeta)>
 {
 	let (progress, _) = evscode::Progress::new().title("ICIE Scan").show();
+	if let Err(e) = net::configure_sites() {
+		e.warning().emit();
+	}
 	let domains = BACKENDS
 		.iter()
 		.filter(|backend| backend.backend.supports_contests())
@@ -18,6 +18,7 @@ mod net;
 mod newsletter;
 mod paste;
 mod service;
+mod standings;
 mod submit;
 mod telemetry;
 mod template;
@@ -51,8 +52,11 @@ evscode::plugin! {
 	],
 	vscode_version: "^1.33.0",
 	node_dependencies: &[
+		("http-proxy-agent", "4.0.1"),
+		("https-proxy-agent", "5.0.0"),
 		("keytar", "5.0.0-beta.3"),
 		("node-fetch", "2.6.0"),
+		("socks-proxy-agent", "5.0.0"),
 		("vscode-extension-telemetry", "0.1.2")
 	],
 	telemetry_key: "d131172a-874d-4c0a-b02f-dbf4c951de3c",
@@ -1,18 +1,24 @@
 use crate::util::{fs, path::Path};
+use async_trait::async_trait;
 use evscode::{error::ResultExt, R};
-use serde::{Deserialize, Serialize};
+pub use icie_common::Manifest;
 use unijudge::Statement;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Manifest {
-	#[serde(default)]
-	pub task_url: Option<String>,
-	#[serde(default)]
-	pub statement: Option<Statement>,
+/// Reading and writing the manifest of the project opened in VS Code.
+#[async_trait(?Send)]
+pub trait ManifestExt: Sized {
+	async fn save(&self, root: &Path) -> R<()>;
+
+	async fn load() -> R<Self>;
+
+	fn req_statement(&self) -> R<&Statement>;
+
+	fn req_task_url(&self) -> R<&str>;
 }
 
-impl Manifest {
-	pub async fn save(&self, root: &Path) -> R<()> {
+#[async_trait(?Send)]
+impl ManifestExt for Manifest {
+	async fn save(&self, root: &Path) -> R<()> {
 		fs::create_dir_all(&root.parent()).await?;
 		let written = serde_json::to_string(self).wrap("failed to serialize the manifest")?;
 		let path = root.join(".icie");
@@ -20,7 +26,7 @@ impl Manifest {
 		Ok(())
 	}
 
-	pub async fn load() -> R<Manifest> {
+	async fn load() -> R<Manifest> {
 		let path = Path::from_native(evscode::workspace_root()?).join(".icie");
 		let s = fs::read_to_string(&path)
 			.await
@@ -30,14 +36,14 @@ impl Manifest {
 		Ok(manifest)
 	}
 
-	pub fn req_statement(&self) -> R<&Statement> {
+	fn req_statement(&self) -> R<&Statement> {
 		self.statement.as_ref().wrap(
 			"could not find statement, make sure site supports it and task was opened with Alt+F9 \
 			 or Alt+F11",
 		)
 	}
 
-	pub fn req_task_url(&self) -> R<&str> {
+	fn req_task_url(&self) -> R<&str> {
 		Ok(self
 			.task_url
 			.as_ref()
@@ -0,0 +1,55 @@
+//! Spacing out operations on the same site, shared by all sessions. Operations are started at least
+//! [`OPERATION_INTERVAL`] apart, and when a site reports it is overloaded, all operations on it are
+//! postponed by an exponential backoff with jitter, or by as much as the site asked for.
+
+use crate::util::{sleep, time_now};
+use log::debug;
+use once_cell::sync::Lazy;
+use std::{
+	cmp::{max, min}, collections::HashMap, sync::Mutex, time::{Duration, SystemTime}
+};
+
+const OPERATION_INTERVAL: Duration = Duration::from_millis(500);
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_LIMIT: Duration = Duration::from_secs(60);
+/// Waits shorter than this are not shown in the status bar, to avoid flickering.
+const STATUS_THRESHOLD: Duration = Duration::from_secs(1);
+
+static NEXT_ALLOWED: Lazy<Mutex<HashMap<String, SystemTime>>> =
+	Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Waits until an operation on the domain is allowed to start, and reserves a slot for it.
+pub async fn acquire(domain: &str) {
+	let now = time_now();
+	let start = {
+		let mut next_allowed = NEXT_ALLOWED.lock().unwrap();
+		let next = next_allowed.entry(domain.to_owned()).or_insert(now);
+		let start = max(*next, now);
+		*next = start + OPERATION_INTERVAL;
+		start
+	};
+	let wait = start.duration_since(now).unwrap_or_default();
+	if wait > Duration::from_secs(0) {
+		debug!("icie.net.schedule.acquire, domain = {:?}, wait = {:?}", domain, wait);
+		let _status = if wait >= STATUS_THRESHOLD {
+			Some(crate::STATUS.push(format!("Waiting {}s for {}", wait.as_secs(), domain)))
+		} else {
+			None
+		};
+		sleep(wait).await;
+	}
+}
+
+/// Postpones all operations on the domain after the site rejected one due to a rate limit. The
+/// attempt number starts at zero and doubles the backoff every time.
+pub fn back_off(domain: &str, attempt: u32, retry_after: Option<Duration>) {
+	let backoff = min(BACKOFF_BASE * 2u32.saturating_pow(attempt), BACKOFF_LIMIT);
+	// Half of the backoff is random, so that sessions limited at the same time do not all retry at
+	// once.
+	let jitter = backoff.div_f64(2.0).mul_f64(js_sys::Math::random());
+	let delay = max(backoff.div_f64(2.0) + jitter, retry_after.unwrap_or_default());
+	debug!("icie.net.schedule.back_off, domain = {:?}, delay = {:?}", domain, delay);
+	let mut next_allowed = NEXT_ALLOWED.lock().unwrap();
+	let next = next_allowed.entry(domain.to_owned()).or_insert_with(time_now);
+	*next = max(*next, time_now() + delay);
+}
@@ -0,0 +1,17 @@
+pub mod manage;
+mod render;
+
+use crate::telemetry::TELEMETRY;
+use evscode::R;
+
+/// How often the standings view is refreshed while it is open, in seconds.
+#[evscode::config]
+static REFRESH_INTERVAL: evscode::Config<u64> = 60u64;
+
+#[evscode::command(title = "ICIE Standings")]
+async fn open() -> R<()> {
+	TELEMETRY.standings_open.spark();
+	let (webview, _) = manage::WEBVIEW.get_force(()).await?;
+	webview.reveal(1, false);
+	Ok(())
+}
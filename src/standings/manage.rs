@@ -0,0 +1,65 @@
+use crate::{
+	manifest::{Manifest, ManifestExt}, net::{self, require_task}, standings::{render::render, REFRESH_INTERVAL}, util::sleep
+};
+use async_trait::async_trait;
+use evscode::{
+	error::{cancel_on, ResultExt}, goodies::webview_collection::{Behaviour, Collection}, webview::{Disposer, Listener, WebviewMeta, WebviewRef}, R
+};
+use futures::StreamExt;
+use std::time::Duration;
+use unijudge::{Backend, Resource, Standings};
+
+lazy_static::lazy_static! {
+	pub static ref WEBVIEW: Collection<StandingsView> = Collection::new(StandingsView);
+}
+
+pub struct StandingsView;
+
+#[async_trait(?Send)]
+impl Behaviour for StandingsView {
+	type K = ();
+	type V = Standings;
+
+	fn create_empty(&self, _: Self::K) -> R<WebviewMeta> {
+		Ok(evscode::Webview::new("icie.standings", "ICIE Standings", 1).create())
+	}
+
+	async fn compute(&self, _: Self::K) -> R<Self::V> {
+		let _status = crate::STATUS.push("Fetching standings");
+		let manifest = Manifest::load().await?;
+		let (url, backend) = net::interpret_url(manifest.req_task_url()?)?;
+		let url = require_task(url)?;
+		let Resource::Task(task) = url.resource;
+		let contest =
+			backend.backend.task_contest(&task).wrap("task is not attached to any contest")?;
+		let sess = net::Session::connect(&url.domain, backend).await?;
+		sess.run(|backend, sess| backend.contest_standings(sess, &contest)).await
+	}
+
+	async fn update(&self, _: Self::K, standings: &Self::V, webview: WebviewRef) -> R<()> {
+		webview.set_html(&render(standings));
+		Ok(())
+	}
+
+	async fn manage(
+		&self,
+		key: Self::K,
+		webview: WebviewRef,
+		_: Listener,
+		disposer: Disposer,
+	) -> R<()>
+	{
+		let refreshes = futures::stream::repeat(())
+			.then(|()| sleep(Duration::from_secs(REFRESH_INTERVAL.get())));
+		let mut refreshes = Box::pin(cancel_on(refreshes, disposer));
+		while let Some(refresh) = refreshes.next().await {
+			refresh?;
+			// A failed refresh should not close the view, the next one may well succeed.
+			match self.compute(key).await {
+				Ok(standings) => self.update(key, &standings, webview.clone()).await?,
+				Err(e) => e.context("failed to refresh standings").warning().emit(),
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,84 @@
+use crate::util;
+use std::time::Duration;
+use unijudge::{Standings, StandingsRow, TaskResult};
+
+pub fn render(standings: &Standings) -> String {
+	let has_penalty = standings.rows.iter().any(|row| row.penalty.is_some());
+	format!(
+		r#"
+		<html>
+			<head>
+				<style>{css}</style>
+			</head>
+			<body>
+				<table class="standings">
+					<thead>
+						<tr>
+							<th>#</th>
+							<th>Participant</th>
+							<th>Points</th>
+							{penalty}
+							{tasks}
+						</tr>
+					</thead>
+					<tbody>
+						{rows}
+					</tbody>
+				</table>
+			</body>
+		</html>
+	"#,
+		css = include_str!("./style.css"),
+		penalty = if has_penalty { "<th>Penalty</th>" } else { "" },
+		tasks = standings
+			.tasks
+			.iter()
+			.map(|task| format!("<th>{}</th>", util::html_escape(task)))
+			.collect::<String>(),
+		rows = standings.rows.iter().map(|row| render_row(row, has_penalty)).collect::<String>(),
+	)
+}
+
+fn render_row(row: &StandingsRow, has_penalty: bool) -> String {
+	format!(
+		r#"
+		<tr class="{class}">
+			<td>{rank}</td>
+			<td class="participant">{participant}</td>
+			<td>{points}</td>
+			{penalty}
+			{results}
+		</tr>
+	"#,
+		class = if row.own { "own" } else { "" },
+		rank = row.rank,
+		participant = util::html_escape(&row.participant),
+		points = row.points,
+		penalty = if has_penalty {
+			format!("<td>{}</td>", row.penalty.map(fmt_penalty).unwrap_or_default())
+		} else {
+			String::new()
+		},
+		results = row.results.iter().map(render_result).collect::<String>(),
+	)
+}
+
+fn render_result(result: &Option<TaskResult>) -> String {
+	match result {
+		Some(result) if result.points > 0. => {
+			let rejected = if result.rejected > 0 {
+				format!(" (+{})", result.rejected)
+			} else {
+				String::new()
+			};
+			format!("<td class=\"solved\">{}{}</td>", result.points, rejected)
+		},
+		Some(result) => format!("<td class=\"rejected\">-{}</td>", result.rejected),
+		None => "<td></td>".to_owned(),
+	}
+}
+
+fn fmt_penalty(penalty: Duration) -> String {
+	let minutes = penalty.as_secs() / 60;
+	format!("{}:{:02}", minutes / 60, minutes % 60)
+}
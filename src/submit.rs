@@ -1,21 +1,53 @@
+mod download;
+pub mod panel;
+mod render;
+mod tracked;
+
 use crate::{
-	dir, init::help_init, manifest::Manifest, net::{self, require_task}, telemetry::TELEMETRY, test, util::{fs, sleep}
+	dir, init::help_init, manifest::{Manifest, ManifestExt}, net::{self, require_task}, telemetry::TELEMETRY, test, util::fs
 };
 use evscode::{E, R};
 use log::debug;
 use std::time::Duration;
+use tracked::Tracked;
 use unijudge::{
 	boxed::{BoxedContest, BoxedTask}, Backend, RejectionCause, Resource
 };
 
+/// The minimum total score required to submit, when tests are split into groups using group.json
+/// files in the test subdirectories. Tests placed directly in the test directory, outside of any
+/// group, still have to pass. Leave empty to require all tests to pass, which is also what happens
+/// when there are no groups.
+#[evscode::config]
+static MIN_SCORE: evscode::Config<Option<u64>> = None;
+
 #[evscode::command(title = "ICIE Submit", key = "alt+f12")]
 async fn send() -> R<()> {
 	let _status = crate::STATUS.push("Submitting");
 	TELEMETRY.submit_f12.spark();
 	let (_, report) = crate::test::view::manage::COLLECTION.get_force(None).await?;
-	if report.iter().any(|test| !test.success()) {
-		TELEMETRY.submit_failtest.spark();
-		return Err(E::error("some tests failed, submit aborted").workflow_error());
+	let score = test::groups::score(&report).await?;
+	match (score, MIN_SCORE.get()) {
+		(Some(score), Some(min_score)) if score.earned < min_score => {
+			TELEMETRY.submit_failtest.spark();
+			return Err(E::error(format!(
+				"scored {}/{} which is less than required {}, submit aborted",
+				score.earned, score.total, min_score
+			))
+			.workflow_error());
+		},
+		(Some(score), Some(_)) if score.ungrouped_failed > 0 => {
+			TELEMETRY.submit_failtest.spark();
+			return Err(E::error("some tests outside of groups failed, submit aborted")
+				.workflow_error());
+		},
+		(Some(_), Some(_)) => (),
+		_ => {
+			if report.iter().any(|test| !test.success()) {
+				TELEMETRY.submit_failtest.spark();
+				return Err(E::error("some tests failed, submit aborted").workflow_error());
+			}
+		},
 	}
 	if report.is_empty() {
 		TELEMETRY.submit_notest.spark();
@@ -34,7 +66,7 @@ async fn send_passed() -> R<()> {
 	let code = dir::solution()?;
 	let code = fs::read_to_string(&code).await?;
 	let manifest = Manifest::load().await?;
-	let url = manifest.req_task_url().map_err(|e| {
+	let task_url = manifest.req_task_url().map_err(|e| {
 		TELEMETRY.submit_notask.spark();
 		e.context(
 			"submit aborted, either open a task/contest to be able to submit, or use Alt+0 to \
@@ -42,7 +74,7 @@ async fn send_passed() -> R<()> {
 		)
 		.action("How to open tasks?", help_init())
 	})?;
-	let (url, backend) = net::interpret_url(url)?;
+	let (url, backend) = net::interpret_url(task_url)?;
 	let url = require_task::<BoxedContest, BoxedTask>(url)?;
 	debug!("icie.submit.send_passed url = {:?}", url);
 	let Resource::Task(task) = url.resource;
@@ -62,59 +94,40 @@ async fn send_passed() -> R<()> {
 	debug!("icie.submit.send_passed found c++");
 	let submit_id = sess.run(|backend, sess| backend.task_submit(sess, &task, lang, &code)).await?;
 	debug!("icie.submit.send_passed received submit id");
+	tracked::add(Tracked {
+		scope_url: scope_url(task_url)?,
+		task_url: task_url.to_owned(),
+		id: submit_id,
+		pending: true,
+		not_seen: 0,
+	})
+	.await?;
 	drop(_status);
-	track(sess, &task, submit_id).await?;
+	let (webview, _) = panel::WEBVIEW.get_force(()).await?;
+	webview.reveal(2, true);
+	Ok(())
+}
+
+#[evscode::command(title = "ICIE Submissions")]
+async fn submissions() -> R<()> {
+	TELEMETRY.submit_panel.spark();
+	let (webview, _) = panel::WEBVIEW.get_force(()).await?;
+	webview.reveal(2, false);
 	Ok(())
 }
 
 const TRACK_DELAY: Duration = Duration::from_secs(5);
 const TRACK_NOT_SEEN_RETRY_LIMIT: usize = 4;
-const TRACK_NOT_SEEN_RETRY_DELAY: Duration = Duration::from_secs(5);
 
-async fn track(sess: crate::net::Session, url: &unijudge::boxed::BoxedTask, id: String) -> R<()> {
-	let _status = crate::STATUS.push("Tracking");
-	let submission_url = sess
-		.run(|backend, sess| futures::future::ok(backend.submission_url(sess, &url, &id)))
-		.await?;
-	let progress = evscode::Progress::new()
-		.title(format!("Tracking submit [#{}]({})", id, submission_url))
-		.show()
-		.0;
-	let mut last_verdict = None;
-	let mut not_seen_retry_limit = TRACK_NOT_SEEN_RETRY_LIMIT;
-	let verdict = loop {
-		let submissions = {
-			let _status = crate::STATUS.push("Refreshing...");
-			sess.run(|backend, sess| backend.task_submissions(sess, &url)).await?
-		};
-		let submission = match submissions.into_iter().find(|subm| subm.id == id) {
-			Some(submission) => submission,
-			None if not_seen_retry_limit > 0 => {
-				let _status = crate::STATUS.push("Retrying...");
-				not_seen_retry_limit -= 1;
-				sleep(TRACK_NOT_SEEN_RETRY_DELAY).await;
-				continue;
-			},
-			None => {
-				return Err(E::error(format!("submission {} not found on status page", id)));
-			},
-		};
-		let should_send = match &submission.verdict {
-			unijudge::Verdict::Pending { .. } => false,
-			_ => true,
-		};
-		if should_send {
-			break submission.verdict;
-		} else if Some(&submission.verdict) != last_verdict.as_ref() {
-			progress.message(fmt_verdict(&submission.verdict));
-			last_verdict = Some(submission.verdict);
-		}
-		sleep(TRACK_DELAY).await;
-	};
-	progress.end();
-	let message = fmt_verdict(&verdict);
-	evscode::Message::new::<()>(&message).show().await;
-	Ok(())
+/// Returns the URL of the contest the task belongs to, or of the task itself if there is none.
+/// Submissions are grouped by it in the submissions panel.
+fn scope_url(task_url: &str) -> R<String> {
+	let (url, backend) = net::interpret_url(task_url)?;
+	let Resource::Task(task) = require_task(url)?.resource;
+	Ok(match backend.backend.task_contest(&task) {
+		Some(contest) => backend.backend.contest_url(&contest),
+		None => task_url.to_owned(),
+	})
 }
 
 fn fmt_verdict(verdict: &unijudge::Verdict) -> String {
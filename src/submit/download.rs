@@ -0,0 +1,56 @@
+use crate::{
+	dir, manifest::{Manifest, ManifestExt}, net::{self, require_task}, submit::fmt_verdict, telemetry::TELEMETRY, util::{fs, path::Path}
+};
+use evscode::{quick_pick, QuickPick, E, R};
+use unijudge::{
+	boxed::{BoxedContest, BoxedTask}, chrono::Local, Backend, Resource
+};
+
+#[evscode::command(title = "ICIE Open Past Submission")]
+async fn open_past() -> R<()> {
+	TELEMETRY.submit_open_past.spark();
+	let manifest = Manifest::load().await?;
+	let (url, backend) = net::interpret_url(manifest.req_task_url()?)?;
+	let url = require_task::<BoxedContest, BoxedTask>(url)?;
+	let Resource::Task(task) = url.resource;
+	let sess = net::Session::connect(&url.domain, backend).await?;
+	let submissions = {
+		let _status = crate::STATUS.push("Fetching submissions");
+		sess.run(|backend, sess| backend.task_submissions(sess, &task)).await?
+	};
+	if submissions.is_empty() {
+		return Err(E::error("no submissions to this task were found"));
+	}
+	let id = QuickPick::new()
+		.items(submissions.iter().map(|submission| {
+			let mut details = Vec::new();
+			details.extend(submission.language.clone());
+			details.extend(submission.time.map(|time| {
+				time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+			}));
+			quick_pick::Item::new(
+				submission.id.clone(),
+				format!("#{} {}", submission.id, fmt_verdict(&submission.verdict)),
+			)
+			.description(details.join(", "))
+		}))
+		.show()
+		.await
+		.ok_or_else(E::cancel)?;
+	let path = submission_path(&id)?;
+	// The file might have been edited since it was downloaded, so it is not overwritten.
+	if !fs::exists(&path).await? {
+		let code = {
+			let _status = crate::STATUS.push("Downloading submission");
+			sess.run(|backend, sess| backend.submission_source(sess, &task, &id)).await?
+		};
+		fs::write(&path, code).await?;
+	}
+	evscode::open_editor(&path).open().await?;
+	Ok(())
+}
+
+fn submission_path(id: &str) -> R<Path> {
+	let name = format!("submission-{}", id);
+	Ok(dir::solution()?.parent().join(name).with_extension(&*dir::CPP_EXTENSION.get()))
+}
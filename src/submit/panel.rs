@@ -0,0 +1,180 @@
+use crate::{
+	manifest::{Manifest, ManifestExt}, net::{self, require_task}, submit::{fmt_verdict, render::render, tracked, TRACK_DELAY, TRACK_NOT_SEEN_RETRY_LIMIT}, util::sleep
+};
+use async_trait::async_trait;
+use evscode::{
+	error::cancel_on, goodies::webview_collection::{Behaviour, Collection}, webview::{Disposer, Listener, WebviewMeta, WebviewRef}, E, R
+};
+use futures::StreamExt;
+use unijudge::{
+	boxed::{BoxedContest, BoxedTask}, Backend, Resource, Submission, Verdict
+};
+
+lazy_static::lazy_static! {
+	pub static ref WEBVIEW: Collection<Panel> = Collection::new(Panel);
+}
+
+pub struct Panel;
+
+#[derive(Debug)]
+pub struct Row {
+	pub task_url: String,
+	pub id: String,
+	pub url: String,
+	/// Current verdict, or `None` if the submission has not appeared on the site yet.
+	pub verdict: Option<Verdict>,
+	/// Whether the submission was not found on the site too many times, so it is not tracked.
+	pub missing: bool,
+}
+
+#[async_trait(?Send)]
+impl Behaviour for Panel {
+	type K = ();
+	type V = Vec<Row>;
+
+	fn create_empty(&self, _: Self::K) -> R<WebviewMeta> {
+		Ok(evscode::Webview::new("icie.submit.panel", "ICIE Submissions", 2)
+			.preserve_focus()
+			.create())
+	}
+
+	async fn compute(&self, _: Self::K) -> R<Self::V> {
+		let _status = crate::STATUS.push("Refreshing submissions");
+		let manifest = Manifest::load().await?;
+		let task_url = manifest.req_task_url()?;
+		let scope_url = super::scope_url(task_url)?;
+		let all_tracked = tracked::load(&scope_url)?;
+		let (url, backend) = net::interpret_url(task_url)?;
+		let url = require_task::<BoxedContest, BoxedTask>(url)?;
+		let Resource::Task(task) = url.resource;
+		let contest = backend.backend.task_contest(&task);
+		let mut tasks: Vec<(String, BoxedTask)> = Vec::new();
+		for tracked in &all_tracked {
+			if tasks.iter().all(|(task_url, _)| *task_url != tracked.task_url) {
+				let url = require_task(net::interpret_url(&tracked.task_url)?.0)?;
+				let Resource::Task(task) = url.resource;
+				tasks.push((tracked.task_url.clone(), task));
+			}
+		}
+		let sess = net::Session::connect(&url.domain, backend).await?;
+		let submissions = fetch_submissions(&sess, contest.as_ref(), &tasks).await?;
+		let mut finished = Vec::new();
+		let mut not_seen = Vec::new();
+		let mut rows = Vec::new();
+		for tracked in all_tracked.iter().rev() {
+			let submission = submissions.iter().find(|submission| submission.id == tracked.id);
+			let verdict = submission.map(|submission| submission.verdict.clone());
+			let missing = verdict.is_none()
+				&& (!tracked.pending || tracked.not_seen >= TRACK_NOT_SEEN_RETRY_LIMIT);
+			match &verdict {
+				None if missing && tracked.pending => {
+					finished.push(tracked.id.as_str());
+					E::error(format!("submission #{} not found on the site", tracked.id)).emit();
+				},
+				None if tracked.pending => not_seen.push(tracked.id.as_str()),
+				Some(Verdict::Pending { .. }) | None => (),
+				Some(verdict) if tracked.pending => {
+					finished.push(tracked.id.as_str());
+					let message = format!("Submission #{} {}", tracked.id, fmt_verdict(verdict));
+					evscode::spawn(async move {
+						evscode::Message::new::<()>(&message).show().await;
+						Ok(())
+					});
+				},
+				Some(_) => (),
+			}
+			let (_, task) =
+				tasks.iter().find(|(task_url, _)| *task_url == tracked.task_url).unwrap();
+			rows.push(Row {
+				task_url: tracked.task_url.clone(),
+				id: tracked.id.clone(),
+				url: backend.backend.submission_url(&sess.session, task, &tracked.id),
+				verdict,
+				missing,
+			});
+		}
+		if !finished.is_empty() || !not_seen.is_empty() {
+			tracked::record_check(&scope_url, &finished, &not_seen).await?;
+		}
+		Ok(rows)
+	}
+
+	async fn update(&self, _: Self::K, rows: &Self::V, webview: WebviewRef) -> R<()> {
+		webview.set_html(&render(rows));
+		Ok(())
+	}
+
+	async fn manage(
+		&self,
+		key: Self::K,
+		webview: WebviewRef,
+		_: Listener,
+		disposer: Disposer,
+	) -> R<()>
+	{
+		let ticks = futures::stream::repeat(()).then(|()| sleep(TRACK_DELAY));
+		let mut ticks = Box::pin(cancel_on(ticks, disposer));
+		while let Some(tick) = ticks.next().await {
+			tick?;
+			// Judged submissions do not change anymore, so the site is only polled while some are
+			// still pending.
+			if !has_pending().await? {
+				continue;
+			}
+			match self.compute(key).await {
+				Ok(rows) => self.update(key, &rows, webview.clone()).await?,
+				Err(e) => e.context("failed to refresh submissions").warning().emit(),
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Reopens the panel if any submissions in the current contest were still pending when the window
+/// was closed, so that their tracking continues.
+pub async fn resume() -> R<()> {
+	if has_pending().await? {
+		WEBVIEW.get_lazy(()).await?;
+	}
+	Ok(())
+}
+
+async fn has_pending() -> R<bool> {
+	let manifest = match Manifest::load().await {
+		Ok(manifest) => manifest,
+		Err(_) => return Ok(false),
+	};
+	let task_url = match &manifest.task_url {
+		Some(task_url) => task_url,
+		None => return Ok(false),
+	};
+	Ok(tracked::load(&super::scope_url(task_url)?)?.iter().any(|tracked| tracked.pending))
+}
+
+/// Fetches submissions to the whole contest with a single request if the site supports that, and
+/// falls back to querying each task separately otherwise.
+async fn fetch_submissions(
+	sess: &net::Session,
+	contest: Option<&BoxedContest>,
+	tasks: &[(String, BoxedTask)],
+) -> R<Vec<Submission>>
+{
+	if let Some(contest) = contest {
+		let submissions = sess
+			.run(|backend, sess| async move {
+				match backend.contest_submissions(sess, contest).await {
+					Err(unijudge::Error::UnsupportedFeature) => Ok(None),
+					result => result.map(Some),
+				}
+			})
+			.await?;
+		if let Some(submissions) = submissions {
+			return Ok(submissions);
+		}
+	}
+	let mut submissions = Vec::new();
+	for (_, task) in tasks {
+		submissions.extend(sess.run(|backend, sess| backend.task_submissions(sess, task)).await?);
+	}
+	Ok(submissions)
+}
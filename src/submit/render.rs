@@ -0,0 +1,61 @@
+use crate::{submit::{fmt_verdict, panel::Row}, util};
+use unijudge::Verdict;
+
+pub fn render(rows: &[Row]) -> String {
+	format!(
+		r#"
+		<html>
+			<head>
+				<style>{css}</style>
+			</head>
+			<body>
+				<table class="submissions">
+					<thead>
+						<tr>
+							<th>#</th>
+							<th>Task</th>
+							<th>Verdict</th>
+						</tr>
+					</thead>
+					<tbody>
+						{rows}
+					</tbody>
+				</table>
+			</body>
+		</html>
+	"#,
+		css = include_str!("./style.css"),
+		rows = rows.iter().map(render_row).collect::<String>(),
+	)
+}
+
+fn render_row(row: &Row) -> String {
+	let (class, verdict) = match &row.verdict {
+		Some(verdict @ Verdict::Accepted) | Some(verdict @ Verdict::Scored { cause: None, .. }) => {
+			("accepted", fmt_verdict(verdict))
+		},
+		Some(verdict @ Verdict::Pending { .. }) => ("pending", fmt_verdict(verdict)),
+		Some(verdict) => ("rejected", fmt_verdict(verdict)),
+		None if row.missing => ("rejected", "Not found on the site".to_owned()),
+		None => ("pending", "Not yet visible on the site".to_owned()),
+	};
+	// Task URL paths end with the task identifier on all supported sites, which is short enough to
+	// display.
+	let path = row.task_url.split('?').next().unwrap_or(&row.task_url);
+	let task = path.trim_end_matches('/').rsplit('/').next().unwrap_or(path);
+	format!(
+		r#"
+		<tr>
+			<td><a href="{url}">{id}</a></td>
+			<td><a href="{task_url}">{task}</a></td>
+			<td class="{class}">{verdict}</td>
+		</tr>
+	"#,
+		url = util::html_escape(&row.url),
+		id = util::html_escape(&row.id),
+		task_url = util::html_escape(&row.task_url),
+		task = util::html_escape(task),
+		class = class,
+		verdict = util::html_escape(&verdict),
+	)
+}
@@ -0,0 +1,65 @@
+//! Submissions sent through ICIE are remembered in the global state, so that their verdicts can
+//! still be tracked after the window reloads, e.g. when moving to the next task of a contest.
+
+use evscode::{marshal::Marshal, state::Scope, State, R};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+const TRACKED: State<Vec<Tracked>> = State::new("icie.submit.tracked", Scope::Global);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Tracked {
+	/// URL of the contest, or of the task if it is not a part of any contest.
+	pub scope_url: String,
+	pub task_url: String,
+	pub id: String,
+	/// Whether the verdict was still pending when it was last checked. Submissions which were not
+	/// found on the site too many times are not pending either.
+	pub pending: bool,
+	/// How many times the submission was not found on the site when checking.
+	pub not_seen: usize,
+}
+
+impl Marshal for Tracked {
+	fn to_js(&self) -> JsValue {
+		JsValue::from_serde(self).unwrap()
+	}
+
+	fn from_js(raw: JsValue) -> Result<Self, String> {
+		raw.into_serde().map_err(|e| e.to_string())
+	}
+}
+
+pub fn load(scope_url: &str) -> R<Vec<Tracked>> {
+	Ok(TRACKED
+		.get()?
+		.unwrap_or_default()
+		.into_iter()
+		.filter(|tracked| tracked.scope_url == scope_url)
+		.collect())
+}
+
+pub async fn add(tracked: Tracked) -> R<()> {
+	let mut all = TRACKED.get()?.unwrap_or_default();
+	// Submissions are only displayed for the current contest, so the judged ones from other contests
+	// will not be needed anymore.
+	all.retain(|old| old.pending || old.scope_url == tracked.scope_url);
+	all.push(tracked);
+	TRACKED.set(&all).await;
+	Ok(())
+}
+
+/// Saves the outcome of checking the verdicts, with the submissions that should not be pending
+/// anymore and the ones that were not found on the site.
+pub async fn record_check(scope_url: &str, finished: &[&str], not_seen: &[&str]) -> R<()> {
+	let mut all = TRACKED.get()?.unwrap_or_default();
+	for tracked in all.iter_mut().filter(|tracked| tracked.scope_url == scope_url) {
+		if finished.contains(&tracked.id.as_str()) {
+			tracked.pending = false;
+		} else if not_seen.contains(&tracked.id.as_str()) {
+			tracked.not_seen += 1;
+		}
+	}
+	TRACKED.set(&all).await;
+	Ok(())
+}
@@ -14,12 +14,15 @@ impl Counter {
 pub struct Events {
 	pub auth_ask: Counter,
 	pub auth_reset: Counter,
+	pub auth_select_profile: Counter,
 	pub build_all: Counter,
 	pub build_manual: Counter,
 	pub checker_exists: Counter,
+	pub checker_select: Counter,
 	pub debug_gdb: Counter,
 	pub debug_rr: Counter,
 	pub discover_start: Counter,
+	pub init_companion: Counter,
 	pub init_countdown: Counter,
 	pub init_countdown_ok: Counter,
 	pub init_scan: Counter,
@@ -37,6 +40,7 @@ pub struct Events {
 	pub paste_qistruct: Counter,
 	pub paste_quick: Counter,
 	pub paste_quick_ok: Counter,
+	pub standings_open: Counter,
 	pub statement: Counter,
 	pub statement_html: Counter,
 	pub statement_pdf: Counter,
@@ -46,6 +50,8 @@ pub struct Events {
 	pub submit_notask: Counter,
 	pub submit_notest: Counter,
 	pub submit_failtest: Counter,
+	pub submit_open_past: Counter,
+	pub submit_panel: Counter,
 	pub template_instantiate: Counter,
 	pub template_load: Counter,
 	pub template_load_builtin: Counter,
@@ -59,18 +65,22 @@ pub struct Events {
 	pub test_edit: Counter,
 	pub test_eval: Counter,
 	pub test_input: Counter,
+	pub test_judge: Counter,
 	pub test_run: Counter,
 }
 
 pub static TELEMETRY: Events = Events {
 	auth_ask: Counter::new("action.auth_ask"),
 	auth_reset: Counter::new("action.auth_reset"),
+	auth_select_profile: Counter::new("action.auth_select_profile"),
 	build_all: Counter::new("action.build_all"),
 	build_manual: Counter::new("action.build_manual"),
 	checker_exists: Counter::new("action.checker_exists"),
+	checker_select: Counter::new("action.checker_select"),
 	debug_gdb: Counter::new("action.debug_gdb"),
 	debug_rr: Counter::new("action.debug_rr"),
 	discover_start: Counter::new("action.discover_start"),
+	init_companion: Counter::new("action.init_companion"),
 	init_countdown: Counter::new("action.init_countdown"),
 	init_countdown_ok: Counter::new("action.init_countdown_ok"),
 	init_scan: Counter::new("action.init_scan"),
@@ -88,6 +98,7 @@ pub static TELEMETRY: Events = Events {
 	paste_qistruct: Counter::new("action.paste_qistruct"),
 	paste_quick: Counter::new("action.paste_quick"),
 	paste_quick_ok: Counter::new("action.paste_quick_ok"),
+	standings_open: Counter::new("action.standings_open"),
 	statement: Counter::new("action.statement"),
 	statement_html: Counter::new("action.statement_html"),
 	statement_pdf: Counter::new("action.statement_pdf"),
@@ -97,6 +108,8 @@ pub static TELEMETRY: Events = Events {
 	submit_notask: Counter::new("action.submit_notask"),
 	submit_notest: Counter::new("action.submit_notests"),
 	submit_failtest: Counter::new("action.submit_failtest"),
+	submit_open_past: Counter::new("action.submit_open_past"),
+	submit_panel: Counter::new("action.submit_panel"),
 	template_instantiate: Counter::new("action.template_instantiate"),
 	template_load: Counter::new("action.template_load"),
 	template_load_builtin: Counter::new("action.template_load_builtin"),
@@ -110,5 +123,6 @@ pub static TELEMETRY: Events = Events {
 	test_edit: Counter::new("action.test_edit"),
 	test_eval: Counter::new("action.test_eval"),
 	test_input: Counter::new("action.test_input"),
+	test_judge: Counter::new("action.test_judge"),
 	test_run: Counter::new("action.test_run"),
 };
@@ -85,7 +85,8 @@ impl External {
 				args.push("-e");
 				args.push(&command);
 			}
-			let run = program.run("", &args, &Environment { time_limit: None, cwd: None }).await?;
+			let environment = Environment { time_limit: None, memory_limit: None, cwd: None };
+			let run = program.run("", &args, &environment).await?;
 			if run.success() {
 				Ok(())
 			} else {
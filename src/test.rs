@@ -1,15 +1,20 @@
+pub mod diff;
+pub mod groups;
 pub mod judge;
+pub mod remote;
 pub mod scan;
 pub mod view;
 
 use crate::{
-	build::{self, Codegen}, checker::Checker, dir, executable::{Environment, Executable}, telemetry::TELEMETRY, test::{
-		judge::{simple_test, Outcome}, scan::scan_and_order
+	build::{self, Codegen}, checker::Checker, dir, executable::{Environment, Executable}, manifest::{Manifest, ManifestExt}, telemetry::TELEMETRY, test::{
+		judge::{simple_test, Outcome, Verdict}, scan::scan_and_order
 	}, util, util::{fs, path::Path}
 };
-use evscode::{error::ResultExt, webview::WebviewRef, R};
+use evscode::{error::ResultExt, webview::WebviewRef, E, R};
 use futures::{SinkExt, Stream, StreamExt};
-use std::time::Duration;
+use std::{
+	sync::atomic::{AtomicUsize, Ordering::SeqCst}, time::Duration
+};
 
 #[derive(Debug)]
 pub struct TestRun {
@@ -26,22 +31,51 @@ impl TestRun {
 #[derive(Debug)]
 pub struct Task {
 	pub checker: Box<dyn Checker+Send+Sync>,
+	pub interactor: Option<Executable>,
 	pub environment: Environment,
+	/// Name of the file the solution reads input from, if it does not use stdin.
+	pub input_file: Option<String>,
+	/// Name of the file the solution writes output to, if it does not use stdout.
+	pub output_file: Option<String>,
 }
 
 /// The maximum time an executable can run before getting a Time Limit Exceeded verdict, specified
 /// in milliseconds. Leaving this empty(which denotes no limit) is not recommended, because this
-/// will cause stuck processes to run indefinitely, wasting system resources.
+/// will cause stuck processes to run indefinitely, wasting system resources. If the task was
+/// downloaded from a site that states its time limit, that limit is used instead.
 #[evscode::config]
 static TIME_LIMIT: evscode::Config<Option<u64>> = Some(1500);
 
+/// The maximum memory an executable can use before getting a Memory Limit Exceeded verdict,
+/// specified in megabytes. This is only enforced on Linux, where the peak memory usage is measured
+/// by sampling the process periodically, so very short spikes can go unnoticed. Leave empty to
+/// denote no limit. If the task was downloaded from a site that states its memory limit, that
+/// limit is used instead.
+#[evscode::config]
+static MEMORY_LIMIT: evscode::Config<Option<u64>> = None;
+
+/// The number of tests that will be run at the same time. Increasing this can make testing much
+/// faster, but running more tests at once than there are CPU cores available will make the time
+/// measurements unreliable. Results are always displayed in the same order, regardless of which
+/// tests finish first.
+#[evscode::config]
+static CONCURRENCY: evscode::Config<u64> = 1u64;
+
 pub async fn run(main_source: &Option<Path>) -> R<Vec<TestRun>> {
 	let _status = crate::STATUS.push("Testing");
 	TELEMETRY.test_run.spark();
 	let solution = build::build(main_source, Codegen::Debug, false).await?;
+	let (input_file, output_file) = io_files().await;
 	let task = Task {
 		checker: crate::checker::get_checker().await?,
-		environment: Environment { time_limit: time_limit(), cwd: None },
+		interactor: get_interactor().await?,
+		environment: Environment {
+			time_limit: time_limit().await,
+			memory_limit: memory_limit().await,
+			cwd: None,
+		},
+		input_file,
+		output_file,
 	};
 	let test_dir_name = dir::TESTS_DIRECTORY.get();
 	let test_dir = dir::tests()?;
@@ -49,68 +83,76 @@ pub async fn run(main_source: &Option<Path>) -> R<Vec<TestRun>> {
 	let mut runs = Vec::new();
 	let test_count = ins.len();
 	let progress = evscode::Progress::new().title(util::fmt_verb("Testing", &main_source)).show().0;
+	warn_if_oversubscribed();
 	let mut worker = run_thread(ins, task, solution);
 	for _ in 0..test_count {
 		let run = worker.next().await.wrap("did not ran all tests due to an internal panic")??;
 		let name =
 			run.in_path.strip_prefix(&test_dir).wrap("found test outside of test directory")?;
-		progress.update_inc(
-			100.0 / test_count as f64,
-			format!(
-				"{} on `{}` in {}",
-				run.outcome.verdict,
-				name,
-				util::fmt_time_short(&run.outcome.time)
-			),
+		let mut message = format!(
+			"{} on `{}` in {}",
+			run.outcome.verdict,
+			name,
+			util::fmt_time_short(&run.outcome.time)
 		);
+		if run.outcome.verdict == Verdict::WrongAnswer && run.outcome.diffable {
+			let desired = fs::read_to_string(&run.out_path).await?;
+			if let Some(first) = diff::diff(&desired, &run.outcome.out).first {
+				message += &format!(", {}", first);
+			}
+		}
+		progress.update_inc(100.0 / test_count as f64, message);
 		runs.push(run);
 	}
 	Ok(runs)
 }
 
-pub fn time_limit() -> Option<Duration> {
-	TIME_LIMIT.get().map(|ms| Duration::from_millis(ms as u64))
+async fn get_interactor() -> R<Option<Executable>> {
+	let interactor = dir::interactor()?;
+	if fs::exists(&interactor).await? {
+		Ok(Some(build::build(interactor, Codegen::Release, false).await?))
+	} else if Manifest::load().await.map_or(false, |manifest| manifest.interactive) {
+		// Without an interactor, the solution would wait for input that never comes.
+		Err(E::error(format!(
+			"task is interactive, write an interactor in {} to test it",
+			interactor
+		))
+		.workflow_error())
+	} else {
+		Ok(None)
+	}
+}
+
+pub async fn time_limit() -> Option<Duration> {
+	let task_limit = Manifest::load().await.ok().and_then(|manifest| manifest.time_limit);
+	task_limit.or_else(|| TIME_LIMIT.get()).map(Duration::from_millis)
+}
+
+pub async fn memory_limit() -> Option<u64> {
+	let task_limit = Manifest::load().await.ok().and_then(|manifest| manifest.memory_limit);
+	task_limit.or_else(|| MEMORY_LIMIT.get().map(|mb| mb * 1024 * 1024))
+}
+
+/// Returns the names of the input and output files used instead of stdin and stdout, as set in the
+/// manifest.
+pub async fn io_files() -> (Option<String>, Option<String>) {
+	match Manifest::load().await {
+		Ok(manifest) => (manifest.input_file, manifest.output_file),
+		Err(_) => (None, None),
+	}
 }
 
 fn run_thread(ins: Vec<Path>, task: Task, solution: Executable) -> impl Stream<Item=R<TestRun>> {
 	let (tx, rx) = futures::channel::mpsc::unbounded();
-	evscode::spawn(async {
+	evscode::spawn(async move {
 		let mut tx = tx;
-		let task = task;
-		let solution = solution;
-		for in_path in ins {
-			// TODO: Refactor try block into a function
-			let r = try {
-				let out_path = in_path.with_extension("out");
-				let alt_path = in_path.with_extension("alt.out");
-				let input = fs::read_to_string(&in_path).await?;
-				let output = match fs::read_to_string(&out_path).await {
-					Ok(output) => Some(output),
-					// Matching on JS errors would be irritating, so let's just do this.
-					Err(ref e) if e.human().contains("ENOENT: no such file or directory") => None,
-					Err(e) => {
-						return Err(e.context(format!("failed to read test out {}", out_path)));
-					},
-				};
-				let alt = if fs::exists(&alt_path).await? {
-					Some(fs::read_to_string(&alt_path).await?)
-				} else {
-					None
-				};
-				let outcome =
-					simple_test(&solution, &input, output.as_deref(), alt.as_deref(), &task)
-						.await
-						.map_err(|e| e.context("failed to run test"))?;
-				let run = TestRun { in_path, out_path, outcome };
-				if tx.send(Ok(run)).await.is_err() {
-					break;
-				}
-			};
-			match r {
-				Ok(()) => (),
-				Err(e) => {
-					let _ = tx.send(Err(e)).await;
-				},
+		// Buffering keeps the results in the same order as the tests were passed in.
+		let mut runs = futures::stream::iter(ins)
+			.map(|in_path| run_test(in_path, &task, &solution))
+			.buffered(concurrency());
+		while let Some(run) = runs.next().await {
+			if tx.send(run).await.is_err() {
+				break;
 			}
 		}
 		Ok(())
@@ -118,6 +160,50 @@ fn run_thread(ins: Vec<Path>, task: Task, solution: Executable) -> impl Stream<I
 	rx
 }
 
+async fn run_test(in_path: Path, task: &Task, solution: &Executable) -> R<TestRun> {
+	let out_path = in_path.with_extension("out");
+	let alt_path = in_path.with_extension("alt.out");
+	let input = fs::read_to_string(&in_path).await?;
+	let output = match fs::read_to_string(&out_path).await {
+		Ok(output) => Some(output),
+		// Matching on JS errors would be irritating, so let's just do this.
+		Err(ref e) if e.human().contains("ENOENT: no such file or directory") => None,
+		Err(e) => return Err(e.context(format!("failed to read test out {}", out_path))),
+	};
+	let alt = if fs::exists(&alt_path).await? {
+		Some(fs::read_to_string(&alt_path).await?)
+	} else {
+		None
+	};
+	let outcome = simple_test(solution, &input, output.as_deref(), alt.as_deref(), task)
+		.await
+		.map_err(|e| e.context("failed to run test"))?;
+	Ok(TestRun { in_path, out_path, outcome })
+}
+
+fn concurrency() -> usize {
+	CONCURRENCY.get().max(1) as usize
+}
+
+/// Concurrency setting used in the last test run, so that the oversubscription warning is only
+/// shown once after it changes instead of on every test run.
+static LAST_CONCURRENCY: AtomicUsize = AtomicUsize::new(0);
+
+fn warn_if_oversubscribed() {
+	let cpus = node_sys::os::cpus().length() as usize;
+	let changed = LAST_CONCURRENCY.swap(concurrency(), SeqCst) != concurrency();
+	if changed && concurrency() > cpus {
+		E::error(format!(
+			"running {} tests at once on {} CPUs will make time measurements unreliable, consider \
+			 lowering icie.test.concurrency",
+			concurrency(),
+			cpus
+		))
+		.warning()
+		.emit();
+	}
+}
+
 #[evscode::command(title = "ICIE Open Test View", key = "alt+0")]
 async fn view() -> R<()> {
 	TELEMETRY.test_alt0.spark();
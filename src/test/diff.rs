@@ -0,0 +1,209 @@
+use std::fmt;
+
+/// Whitespace-separated token, with its position in the source text. Lines and tokens are numbered
+/// starting from 1, and tokens are numbered separately in every line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+	pub text: &'a str,
+	pub start: usize,
+	pub end: usize,
+	pub line: usize,
+	pub column: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diff {
+	/// Indices of tokens in the desired output that do not match.
+	pub desired: Vec<usize>,
+	/// Indices of tokens in the actual output that do not match.
+	pub out: Vec<usize>,
+	pub first: Option<FirstDifference>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FirstDifference {
+	pub line: usize,
+	pub column: usize,
+	pub expected: Option<String>,
+	pub got: Option<String>,
+}
+
+/// Edit distance above which the outputs are considered completely different, to keep the diff fast
+/// when the output is unrelated to the desired one.
+const MAX_EDITS: usize = 1000;
+
+/// Compares the outputs token by token, ignoring whitespace the same way as
+/// `FreeWhitespaceChecker`. Mismatched tokens are found with a shortest edit script, so that a
+/// single missing or extra token does not mark all the following tokens as different.
+pub fn diff(desired: &str, out: &str) -> Diff {
+	let desired_tokens = tokenize(desired);
+	let out_tokens = tokenize(out);
+	let desired_texts = desired_tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+	let out_texts = out_tokens.iter().map(|t| t.text).collect::<Vec<_>>();
+	let prefix = desired_texts.iter().zip(&out_texts).take_while(|(a, b)| a == b).count();
+	let suffix = desired_texts[prefix..]
+		.iter()
+		.rev()
+		.zip(out_texts[prefix..].iter().rev())
+		.take_while(|(a, b)| a == b)
+		.count();
+	let desired_middle = &desired_texts[prefix..desired_texts.len() - suffix];
+	let out_middle = &out_texts[prefix..out_texts.len() - suffix];
+	let (desired, out) = shortest_edit(desired_middle, out_middle)
+		.unwrap_or_else(|| ((0..desired_middle.len()).collect(), (0..out_middle.len()).collect()));
+	let desired = desired.into_iter().map(|i| prefix + i).collect::<Vec<_>>();
+	let out = out.into_iter().map(|i| prefix + i).collect::<Vec<_>>();
+	let first = if desired.is_empty() && out.is_empty() {
+		None
+	} else {
+		let expected = desired_tokens.get(prefix);
+		let got = out_tokens.get(prefix);
+		let position = got.or(expected).unwrap();
+		Some(FirstDifference {
+			line: position.line,
+			column: position.column,
+			expected: expected.map(|t| t.text.to_owned()),
+			got: got.map(|t| t.text.to_owned()),
+		})
+	};
+	Diff { desired, out, first }
+}
+
+/// Finds the tokens which have to be removed from `a` and inserted from `b` to turn `a` into `b`,
+/// using Myers' algorithm. Returns `None` if more than `MAX_EDITS` edits would be necessary.
+fn shortest_edit(a: &[&str], b: &[&str]) -> Option<(Vec<usize>, Vec<usize>)> {
+	let (n, m) = (a.len() as isize, b.len() as isize);
+	let max = (a.len() + b.len()).min(MAX_EDITS) as isize;
+	// Furthest x reached on every diagonal k = x - y, stored at index k + max + 1.
+	let at = |k: isize| (k + max + 1) as usize;
+	let mut v = vec![0; 2 * max as usize + 3];
+	let mut trace = Vec::new();
+	for d in 0..=max {
+		trace.push(v.clone());
+		for k in (-d..=d).step_by(2) {
+			let down = k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]);
+			let mut x = if down { v[at(k + 1)] } else { v[at(k - 1)] + 1 };
+			let mut y = x - k;
+			while x < n && y < m && a[x as usize] == b[y as usize] {
+				x += 1;
+				y += 1;
+			}
+			v[at(k)] = x;
+			if x >= n && y >= m {
+				return Some(backtrack(&trace, at, n, m));
+			}
+		}
+	}
+	None
+}
+
+fn backtrack(
+	trace: &[Vec<isize>],
+	at: impl Fn(isize) -> usize,
+	n: isize,
+	m: isize,
+) -> (Vec<usize>, Vec<usize>)
+{
+	let mut removed = Vec::new();
+	let mut inserted = Vec::new();
+	let (mut x, mut y) = (n, m);
+	for (d, v) in trace.iter().enumerate().skip(1).rev() {
+		let d = d as isize;
+		let k = x - y;
+		let down = k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]);
+		let prev_k = if down { k + 1 } else { k - 1 };
+		let prev_x = v[at(prev_k)];
+		let prev_y = prev_x - prev_k;
+		if down {
+			inserted.push(prev_y as usize);
+		} else {
+			removed.push(prev_x as usize);
+		}
+		x = prev_x;
+		y = prev_y;
+	}
+	removed.reverse();
+	inserted.reverse();
+	(removed, inserted)
+}
+
+pub fn tokenize(text: &str) -> Vec<Token> {
+	let mut tokens = Vec::new();
+	let mut line = 1;
+	let mut column = 1;
+	let mut start = None;
+	for (i, c) in text.char_indices() {
+		if c.is_whitespace() {
+			if let Some(start) = start.take() {
+				tokens.push(Token { text: &text[start..i], start, end: i, line, column });
+				column += 1;
+			}
+			if c == '\n' {
+				line += 1;
+				column = 1;
+			}
+		} else if start.is_none() {
+			start = Some(i);
+		}
+	}
+	if let Some(start) = start {
+		tokens.push(Token { text: &text[start..], start, end: text.len(), line, column });
+	}
+	tokens
+}
+
+impl fmt::Display for FirstDifference {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"first difference at line {}, token {}: expected {}, got {}",
+			self.line,
+			self.column,
+			self.expected.as_deref().unwrap_or("end of output"),
+			self.got.as_deref().unwrap_or("end of output")
+		)
+	}
+}
+
+#[test]
+fn test_tokenize() {
+	let tokens = tokenize(" 1  2\n\n3\t");
+	assert_eq!(tokens, vec![
+		Token { text: "1", start: 1, end: 2, line: 1, column: 1 },
+		Token { text: "2", start: 4, end: 5, line: 1, column: 2 },
+		Token { text: "3", start: 7, end: 8, line: 3, column: 1 },
+	]);
+}
+
+#[test]
+fn test_diff() {
+	let same = diff("1 2\n3\n", "1  2 \n3");
+	assert_eq!(same, Diff { desired: vec![], out: vec![], first: None });
+	let wrong = diff("YES\n1 2 3\n", "YES\n1 5 3\n");
+	assert_eq!(wrong.desired, vec![2]);
+	assert_eq!(wrong.out, vec![2]);
+	assert_eq!(
+		wrong.first.unwrap().to_string(),
+		"first difference at line 2, token 2: expected 2, got 5"
+	);
+	let short = diff("1 2 3", "1 2");
+	assert_eq!(short.desired, vec![2]);
+	assert_eq!(short.out, vec![]);
+	assert_eq!(
+		short.first.unwrap().to_string(),
+		"first difference at line 1, token 3: expected 3, got end of output"
+	);
+	let missing = diff("1 2 3 4 5", "1 3 4 5");
+	assert_eq!(missing.desired, vec![1]);
+	assert_eq!(missing.out, vec![]);
+	assert_eq!(
+		missing.first.unwrap().to_string(),
+		"first difference at line 1, token 2: expected 2, got 3"
+	);
+	let extra = diff("1 2\n3 4", "1 2\n0\n3 4 9");
+	assert_eq!(extra.desired, vec![]);
+	assert_eq!(extra.out, vec![2, 5]);
+	let replaced = diff("a b c d", "a x c y");
+	assert_eq!(replaced.desired, vec![1, 3]);
+	assert_eq!(replaced.out, vec![1, 3]);
+}
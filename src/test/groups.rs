@@ -0,0 +1,168 @@
+use crate::{
+	dir, test::TestRun, util::{fs, path::Path}
+};
+use evscode::{error::ResultExt, R};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Contents of a `group.json` file placed in a test subdirectory.
+#[derive(Debug, Deserialize)]
+struct GroupConfig {
+	#[serde(default)]
+	points: u64,
+	#[serde(default)]
+	dependencies: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct GroupScore {
+	pub name: String,
+	/// Whether all tests of the group and of its dependencies passed. This is tracked separately
+	/// from the points, because groups worth 0 points are often used for the examples.
+	pub passed: bool,
+	pub earned: u64,
+	pub points: u64,
+}
+
+#[derive(Debug)]
+pub struct Score {
+	pub groups: Vec<GroupScore>,
+	pub earned: u64,
+	pub total: u64,
+	/// Number of failed tests placed directly in the test directory, outside of any group.
+	pub ungrouped_failed: usize,
+}
+
+/// Scores the test runs by groups defined in `group.json` files, or returns `None` if no test
+/// directory contains one. A group earns its points if all of its tests and all of its
+/// dependencies passed.
+pub async fn score(runs: &[TestRun]) -> R<Option<Score>> {
+	let test_dir = dir::tests()?;
+	let groups = load_groups(&test_dir).await?;
+	if groups.is_empty() {
+		return Ok(None);
+	}
+	let mut results = HashMap::new();
+	let mut ungrouped_failed = 0;
+	for run in runs {
+		match group_of(&run.in_path, &test_dir) {
+			Some(group) => {
+				let (passed, total) = results.entry(group).or_insert((0, 0));
+				*passed += run.success() as usize;
+				*total += 1;
+			},
+			None => ungrouped_failed += !run.success() as usize,
+		}
+	}
+	Ok(Some(Score { ungrouped_failed, ..compute(&groups, &results) }))
+}
+
+async fn load_groups(test_dir: &Path) -> R<Vec<(String, GroupConfig)>> {
+	let mut groups = Vec::new();
+	if !fs::exists(test_dir).await? {
+		return Ok(groups);
+	}
+	for path in fs::read_dir(test_dir).await? {
+		let config_path = path.join("group.json");
+		if fs::exists(&config_path).await? {
+			let config = fs::read_to_string(&config_path).await?;
+			let config = serde_json::from_str::<GroupConfig>(&config)
+				.wrap(format!("{} is not a valid group description", config_path))?;
+			groups.push((path.file_name(), config));
+		}
+	}
+	groups.sort_by(|a, b| a.0.cmp(&b.0));
+	Ok(groups)
+}
+
+fn group_of(in_path: &Path, test_dir: &Path) -> Option<String> {
+	let relative = in_path.strip_prefix(test_dir).ok()?;
+	let mut components = relative.to_str()?.split(|c| c == '/' || c == '\\');
+	let group = components.next()?;
+	// Tests placed directly in the test directory do not belong to any group.
+	components.next()?;
+	Some(group.to_owned())
+}
+
+fn compute(groups: &[(String, GroupConfig)], results: &HashMap<String, (usize, usize)>) -> Score {
+	let configs: HashMap<&str, &GroupConfig> =
+		groups.iter().map(|(name, config)| (name.as_str(), config)).collect();
+	let mut memo = HashMap::new();
+	let groups = groups
+		.iter()
+		.map(|(name, config)| {
+			let passed = group_passed(name, &configs, results, &mut memo, &mut Vec::new());
+			GroupScore {
+				name: name.clone(),
+				passed,
+				earned: if passed { config.points } else { 0 },
+				points: config.points,
+			}
+		})
+		.collect::<Vec<_>>();
+	let earned = groups.iter().map(|group| group.earned).sum();
+	let total = groups.iter().map(|group| group.points).sum();
+	Score { groups, earned, total, ungrouped_failed: 0 }
+}
+
+fn group_passed<'a>(
+	name: &'a str,
+	configs: &HashMap<&'a str, &'a GroupConfig>,
+	results: &HashMap<String, (usize, usize)>,
+	memo: &mut HashMap<&'a str, bool>,
+	visiting: &mut Vec<&'a str>,
+) -> bool
+{
+	if let Some(passed) = memo.get(name) {
+		return *passed;
+	}
+	// Cyclic dependencies can never be satisfied.
+	if visiting.contains(&name) {
+		return false;
+	}
+	let config = match configs.get(name) {
+		Some(config) => *config,
+		None => return false,
+	};
+	visiting.push(name);
+	let tests_passed = results.get(name).map_or(false, |(passed, total)| passed == total);
+	let passed = tests_passed
+		&& config.dependencies.iter().all(|dependency| {
+			group_passed(dependency, configs, results, memo, visiting)
+		});
+	visiting.pop();
+	memo.insert(name, passed);
+	passed
+}
+
+#[test]
+fn test_compute() {
+	let group = |points, dependencies: &[&str]| GroupConfig {
+		points,
+		dependencies: dependencies.iter().map(|d| (*d).to_owned()).collect(),
+	};
+	let groups = vec![
+		("1".to_owned(), group(30, &[])),
+		("2".to_owned(), group(33, &["1"])),
+		("3".to_owned(), group(37, &["2"])),
+		("4".to_owned(), group(0, &["4"])),
+		("5".to_owned(), group(0, &[])),
+	];
+	let results = vec![
+		("1".to_owned(), (4, 4)),
+		("2".to_owned(), (2, 2)),
+		("3".to_owned(), (1, 3)),
+		("4".to_owned(), (1, 1)),
+		("5".to_owned(), (0, 2)),
+	]
+	.into_iter()
+	.collect();
+	let score = compute(&groups, &results);
+	assert_eq!((score.earned, score.total), (63, 100));
+	assert_eq!(score.groups.iter().map(|group| group.earned).collect::<Vec<_>>(), vec![
+		30, 33, 0, 0, 0
+	]);
+	assert_eq!(score.groups.iter().map(|group| group.passed).collect::<Vec<_>>(), vec![
+		true, true, false, false, false
+	]);
+}
@@ -1,15 +1,19 @@
 use crate::{
-	executable::{Executable, ExitKind}, test::Task
+	checker::Conclusion, executable::{Environment, Executable, ExitKind, Message, Run}, test::Task, util::{fs, Tempdir, Tempfile}
 };
 use evscode::R;
 use std::{fmt, time::Duration};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Verdict {
 	Accepted { alternative: bool },
 	WrongAnswer,
+	PresentationError,
+	Scored { points: f64 },
+	CheckerFailure,
 	RuntimeError,
 	TimeLimitExceeded,
+	MemoryLimitExceeded,
 	IgnoredNoOut,
 }
 
@@ -28,6 +32,15 @@ pub struct Outcome {
 	pub out: String,
 	pub stderr: String,
 	pub time: Duration,
+	pub memory: Option<u64>,
+	/// Message printed by the checker or the interactor to its stderr.
+	pub checker_comment: Option<String>,
+	/// Messages exchanged with the interactor, if the task is interactive.
+	pub transcript: Option<Vec<Message>>,
+	/// Whether the output can be meaningfully compared with the desired output token by token.
+	/// This is not the case for interactive tasks, for checkers which accept differences and for
+	/// tests with an alternative correct output, which the differences could be accepted by.
+	pub diffable: bool,
 }
 
 impl Outcome {
@@ -44,43 +57,150 @@ pub async fn simple_test(
 	task: &Task,
 ) -> R<Outcome>
 {
-	let run = exec.run(input, &[], &task.environment).await?;
-	let verdict = match run.exit_kind {
-		ExitKind::Normal => {
-			if run.success() {
-				if let Some(desired) = desired {
-					if task.checker.judge(input, desired, &run.stdout).await? {
-						Verdict::Accepted { alternative: false }
-					} else if let Some(alternative) = alternative {
-						if task.checker.judge(input, alternative, &run.stdout).await? {
-							Verdict::Accepted { alternative: true }
-						} else {
-							Verdict::WrongAnswer
-						}
-					} else {
-						Verdict::WrongAnswer
-					}
-				} else {
-					Verdict::IgnoredNoOut
-				}
-			} else {
-				Verdict::RuntimeError
-			}
+	if let Some(interactor) = &task.interactor {
+		return interactive_test(exec, interactor, input, task).await;
+	}
+	let run = if task.input_file.is_some() || task.output_file.is_some() {
+		run_with_files(exec, input, task).await?
+	} else {
+		exec.run(input, &[], &task.environment).await?
+	};
+	let (verdict, checker_comment) = match run.exit_kind {
+		ExitKind::Normal if !run.success() => (Verdict::RuntimeError, None),
+		ExitKind::Normal => match desired {
+			Some(desired) => judge_output(input, desired, alternative, &run.stdout, task).await?,
+			None => (Verdict::IgnoredNoOut, None),
 		},
+		ExitKind::TimeLimitExceeded => (Verdict::TimeLimitExceeded, None),
+		ExitKind::MemoryLimitExceeded => (Verdict::MemoryLimitExceeded, None),
+	};
+	Ok(Outcome {
+		verdict,
+		out: run.stdout,
+		stderr: run.stderr,
+		time: run.time,
+		memory: run.memory,
+		checker_comment,
+		transcript: None,
+		diffable: !task.checker.tolerates_differences() && alternative.is_none(),
+	})
+}
+
+/// Runs the solution in a scratch directory, where the input file is created before the run and
+/// the output file is read after it. The output file contents are returned in place of stdout, so
+/// the checkers and the test view do not need to know about the files.
+async fn run_with_files(exec: &Executable, input: &str, task: &Task) -> R<Run> {
+	let scratch = Tempdir::new("io").await?;
+	if let Some(input_file) = &task.input_file {
+		fs::write(&scratch.path().join(input_file), input).await?;
+	}
+	let stdin = if task.input_file.is_some() { "" } else { input };
+	let environment = Environment {
+		time_limit: task.environment.time_limit,
+		memory_limit: task.environment.memory_limit,
+		cwd: Some(scratch.path().clone()),
+	};
+	let mut run = exec.run(stdin, &[], &environment).await?;
+	if let Some(output_file) = &task.output_file {
+		let output_path = scratch.path().join(output_file);
+		run.stdout = if fs::exists(&output_path).await? {
+			fs::read_to_string(&output_path).await?
+		} else {
+			String::new()
+		};
+	}
+	Ok(run)
+}
+
+async fn judge_output(
+	input: &str,
+	desired: &str,
+	alternative: Option<&str>,
+	out: &str,
+	task: &Task,
+) -> R<(Verdict, Option<String>)>
+{
+	let judgement = task.checker.judge(input, desired, out).await?;
+	if judgement.success() {
+		return Ok((Verdict::Accepted { alternative: false }, judgement.comment));
+	}
+	if let Some(alternative) = alternative {
+		let alternative_judgement = task.checker.judge(input, alternative, out).await?;
+		if alternative_judgement.success() {
+			return Ok((Verdict::Accepted { alternative: true }, alternative_judgement.comment));
+		}
+	}
+	let verdict = match judgement.conclusion {
+		Conclusion::Accepted => Verdict::Accepted { alternative: false },
+		Conclusion::WrongAnswer => Verdict::WrongAnswer,
+		Conclusion::PresentationError => Verdict::PresentationError,
+		Conclusion::Failure => Verdict::CheckerFailure,
+		Conclusion::Points(points) => Verdict::Scored { points },
+	};
+	Ok((verdict, judgement.comment))
+}
+
+async fn interactive_test(
+	exec: &Executable,
+	interactor: &Executable,
+	input: &str,
+	task: &Task,
+) -> R<Outcome>
+{
+	let input_file = Tempfile::new("interactor_input", input).await?;
+	let interactor_environment = Environment {
+		time_limit: task.environment.time_limit,
+		memory_limit: None,
+		cwd: task.environment.cwd.clone(),
+	};
+	let interaction = exec
+		.run_interactive(
+			interactor,
+			&[input_file.path().to_str().unwrap()],
+			&task.environment,
+			&interactor_environment,
+		)
+		.await?;
+	let solution = interaction.solution;
+	let interactor = interaction.interactor;
+	// An interactor which was killed or crashed did not decide anything about the solution.
+	let interactor_failed =
+		interactor.exit_kind != ExitKind::Normal || interactor.exit_code.is_none();
+	let verdict = match solution.exit_kind {
+		ExitKind::Normal if !solution.success() => Verdict::RuntimeError,
+		ExitKind::Normal if interactor_failed => Verdict::CheckerFailure,
+		ExitKind::Normal if interactor.success() => Verdict::Accepted { alternative: false },
+		ExitKind::Normal => Verdict::WrongAnswer,
 		ExitKind::TimeLimitExceeded => Verdict::TimeLimitExceeded,
+		ExitKind::MemoryLimitExceeded => Verdict::MemoryLimitExceeded,
 	};
-	Ok(Outcome { verdict, out: run.stdout, stderr: run.stderr, time: run.time })
+	Ok(Outcome {
+		verdict,
+		out: solution.stdout,
+		stderr: solution.stderr,
+		time: solution.time,
+		memory: solution.memory,
+		checker_comment: Some(interactor.stderr.trim().to_owned())
+			.filter(|comment| !comment.is_empty()),
+		transcript: Some(interaction.transcript),
+		diffable: false,
+	})
 }
 
 impl fmt::Display for Verdict {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		use Verdict::*;
-		write!(f, "{}", match self {
+		let name = match self {
 			Accepted { .. } => "Accept",
 			WrongAnswer => "Wrong Answer",
+			PresentationError => "Presentation Error",
+			Scored { points } => return write!(f, "Scored {} points", points),
+			CheckerFailure => "Checker Failure",
 			RuntimeError => "Runtime Error",
 			TimeLimitExceeded => "Time Limit Exceeded",
+			MemoryLimitExceeded => "Memory Limit Exceeded",
 			IgnoredNoOut => "Ignored (no out)",
-		})
+		};
+		write!(f, "{}", name)
 	}
 }
@@ -0,0 +1,63 @@
+use crate::{
+	dir, manifest::{Manifest, ManifestExt}, net::{self, require_task}, util::{self, fs, path::Path, sleep}
+};
+use evscode::{E, R};
+use futures::{select, FutureExt};
+use std::time::Duration;
+use unijudge::{
+	boxed::{BoxedContest, BoxedTask}, Backend, Invocation, Resource
+};
+
+const POLL_DELAY: Duration = Duration::from_secs(2);
+/// Custom invocations are limited to a few seconds of running time, so a run that takes much longer
+/// than that is most likely stuck in the judge's queue.
+const POLL_LIMIT: usize = 60;
+
+/// Runs the solution on the test input using the site's custom invocation, so that differences
+/// between the local compiler and the judge's one can be spotted.
+pub async fn run(source: Option<Path>, in_path: &Path) -> R<Invocation> {
+	let _status = crate::STATUS.push("Running on judge");
+	let source = match source {
+		Some(source) => source,
+		None => dir::solution()?,
+	};
+	let code = fs::read_to_string(&source).await?;
+	let input = fs::read_to_string(in_path).await?;
+	let manifest = Manifest::load().await?;
+	let (url, backend) = net::interpret_url(manifest.req_task_url()?)?;
+	let url = require_task::<BoxedContest, BoxedTask>(url)?;
+	let Resource::Task(task) = url.resource;
+	let sess = net::Session::connect(&url.domain, backend).await?;
+	let langs = sess.run(|backend, sess| backend.task_languages(sess, &task)).await?;
+	let lang = langs
+		.iter()
+		.find(|lang| lang.name == backend.cpp)
+		.ok_or_else(|| E::error(format!("not found language {:?}", backend.cpp)))?;
+	let id = sess
+		.run(|backend, sess| backend.invocation_start(sess, &task, lang, &code, &input))
+		.await?;
+	let (progress, on_cancel) =
+		evscode::Progress::new().title("Waiting for the judge").cancellable().show();
+	let mut on_cancel = on_cancel.boxed().fuse();
+	for _ in 0..POLL_LIMIT {
+		let mut delay = Box::pin(sleep(POLL_DELAY).fuse());
+		select! {
+			() = delay => (),
+			() = on_cancel => return Err(E::cancel()),
+		}
+		if let Some(invocation) =
+			sess.run(|backend, sess| backend.invocation_result(sess, &task, &id)).await?
+		{
+			progress.end();
+			return Ok(invocation);
+		}
+	}
+	Err(E::error("judge did not finish running the test in time"))
+}
+
+pub fn fmt_usage(invocation: &Invocation) -> String {
+	let mut usage = vec!["Judge".to_owned()];
+	usage.extend(invocation.time.as_ref().map(util::fmt_time_short));
+	usage.extend(invocation.memory.map(util::fmt_memory_short));
+	usage.join(" ")
+}
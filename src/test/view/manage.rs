@@ -1,6 +1,6 @@
 use crate::{
 	build::{build, Codegen}, debug::{gdb, rr}, dir, executable::Environment, telemetry::TELEMETRY, test::{
-		add_test, run, time_limit, view::{render::render, SCROLL_TO_FIRST_FAILED, SKILL_ACTIONS}, TestRun
+		add_test, memory_limit, remote, run, time_limit, view::{render::render, SCROLL_TO_FIRST_FAILED, SKILL_ACTIONS}, TestRun
 	}, util::{fmt_verb, fs, path::Path}
 };
 use async_trait::async_trait;
@@ -63,6 +63,22 @@ impl Behaviour for TestView {
 					let source = source.clone();
 					evscode::spawn(gdb(in_path, source));
 				},
+				Note::TriggerJudge { in_path } => {
+					let source = source.clone();
+					let webview = webview.clone();
+					evscode::spawn(async move {
+						TELEMETRY.test_judge.spark();
+						let invocation = remote::run(source, &in_path).await?;
+						webview
+							.post_message(Food::JudgeResp {
+								in_path: in_path.to_str().unwrap().to_owned(),
+								usage: remote::fmt_usage(&invocation),
+								output: invocation.output,
+							})
+							.await;
+						Ok(())
+					});
+				},
 				Note::NewTest { input, desired } => {
 					evscode::spawn(async move { add_test(&input, &desired).await })
 				},
@@ -96,8 +112,11 @@ impl Behaviour for TestView {
 								TELEMETRY.test_eval.spark();
 								let _status = crate::STATUS.push("Evaluating");
 								let brut = build(brut, Codegen::Release, false).await?;
-								let environment =
-									Environment { time_limit: time_limit(), cwd: None };
+								let environment = Environment {
+									time_limit: time_limit().await,
+									memory_limit: memory_limit().await,
+									cwd: None,
+								};
 								let run = brut.run(&input, &[], &environment).await?;
 								drop(_status);
 								if run.success() {
@@ -124,6 +143,8 @@ enum Note {
 	TriggerRR { in_path: Path },
 	#[serde(rename = "trigger_gdb")]
 	TriggerGDB { in_path: Path },
+	#[serde(rename = "trigger_judge")]
+	TriggerJudge { in_path: Path },
 	#[serde(rename = "new_test")]
 	NewTest { input: String, desired: String },
 	#[serde(rename = "set_alt")]
@@ -147,4 +168,6 @@ pub enum Food {
 	EvalResp { id: i64, input: String },
 	#[serde(rename = "new_start")]
 	NewStart,
+	#[serde(rename = "judge_resp")]
+	JudgeResp { in_path: String, usage: String, output: String },
 }
@@ -1,5 +1,7 @@
 use crate::{
-	test::{judge::Verdict, view::SKILL_ACTIONS, TestRun}, util::{self, fs}
+	executable::{Message, Party}, test::{
+		diff::{self, Diff}, groups, judge::Verdict, view::SKILL_ACTIONS, TestRun
+	}, util::{self, fs}
 };
 use evscode::R;
 use std::cmp::max;
@@ -46,6 +48,7 @@ pub async fn render(tests: &[TestRun]) -> R<String> {
 				<style>{css_paint}</style>
 			</head>
 			<body>
+				{score}
 				<table class="table">
 					{table}
 				</table>
@@ -60,10 +63,36 @@ pub async fn render(tests: &[TestRun]) -> R<String> {
 		material_icons = util::html_material_icons(),
 		css_layout = include_str!("./layout.css"),
 		css_paint = include_str!("./paint.css"),
+		score = render_score(tests).await?,
 		table = render_test_table(tests).await?
 	))
 }
 
+async fn render_score(tests: &[TestRun]) -> R<String> {
+	let score = match groups::score(tests).await? {
+		Some(score) => score,
+		None => return Ok(String::new()),
+	};
+	let groups = score
+		.groups
+		.iter()
+		.map(|group| {
+			let status = if group.passed { "passed" } else { "failed" };
+			format!(
+				"<span class=\"group status-{}\">{} {}/{}</span>",
+				status,
+				util::html_escape(&group.name),
+				group.earned,
+				group.points
+			)
+		})
+		.collect::<String>();
+	Ok(format!(
+		"<div class=\"score\"><span class=\"total\">{}/{}</span>{}</div>",
+		score.earned, score.total, groups
+	))
+}
+
 async fn render_test_table(tests: &[TestRun]) -> R<String> {
 	let any_failed = tests.iter().any(|test| !test.success());
 	let mut html = String::new();
@@ -78,6 +107,12 @@ async fn render_test(test: &TestRun, any_failed: bool) -> R<String> {
 		return Ok(String::new());
 	}
 	let folded = test.success() && FOLD_AC.get().should(any_failed);
+	let diff = if test.outcome.verdict == Verdict::WrongAnswer && test.outcome.diffable {
+		let desired = fs::read_to_string(&test.out_path).await?;
+		Some(diff::diff(&desired, &test.outcome.out))
+	} else {
+		None
+	};
 	Ok(format!(
 		r#"
 		<tr class="row {status} {verdict}" data-path_in="{path_in}" data-raw_out="{raw_out}">
@@ -88,23 +123,32 @@ async fn render_test(test: &TestRun, any_failed: bool) -> R<String> {
 		"#,
 		status = match test.outcome.verdict {
 			Verdict::Accepted { .. } => "status-passed",
-			Verdict::WrongAnswer | Verdict::RuntimeError | Verdict::TimeLimitExceeded =>
-				"status-failed",
+			Verdict::WrongAnswer
+			| Verdict::PresentationError
+			| Verdict::CheckerFailure
+			| Verdict::RuntimeError
+			| Verdict::TimeLimitExceeded
+			| Verdict::MemoryLimitExceeded => "status-failed",
+			Verdict::Scored { .. } => "status-partial",
 			Verdict::IgnoredNoOut => "status-ignore",
 		},
 		verdict = match test.outcome.verdict {
 			Verdict::Accepted { alternative: false } => "verdict-accept",
 			Verdict::Accepted { alternative: true } => "verdict-alternative",
 			Verdict::WrongAnswer => "verdict-wrong-answer",
+			Verdict::PresentationError => "verdict-presentation-error",
+			Verdict::Scored { .. } => "verdict-scored",
+			Verdict::CheckerFailure => "verdict-checker-failure",
 			Verdict::RuntimeError => "verdict-runtime-error",
 			Verdict::TimeLimitExceeded => "verdict-time-limit-exceeded",
+			Verdict::MemoryLimitExceeded => "verdict-memory-limit-exceeded",
 			Verdict::IgnoredNoOut => "verdict-ignored",
 		},
-		path_in = html_escape(test.in_path.to_str().unwrap()),
-		raw_out = html_escape(&test.outcome.out),
+		path_in = util::html_escape(test.in_path.to_str().unwrap()),
+		raw_out = util::html_escape(&test.outcome.out),
 		input = render_in_cell(test, folded).await?,
-		output = render_out_cell(test, folded).await?,
-		desired = render_desired_cell(test, folded).await?,
+		output = render_out_cell(test, diff.as_ref(), folded).await?,
+		desired = render_desired_cell(test, diff.as_ref(), folded).await?,
 	))
 }
 
@@ -112,7 +156,7 @@ async fn render_in_cell(test: &TestRun, folded: bool) -> R<String> {
 	let data = fs::read_to_string(&test.in_path).await?;
 	let attrs = [("data-raw", data.as_str())];
 	let actions = [(!HIDE_COPY.get(), ACTION_COPY), (true, ACTION_EDIT)];
-	Ok(render_cell("input", &attrs, &actions, None, &data, None, folded).await)
+	Ok(render_cell("input", &attrs, &actions, None, &data, &[], None, None, folded).await)
 }
 
 /// If a solution takes longer to execute than the specified number of milliseconds, a note with the
@@ -121,7 +165,13 @@ async fn render_in_cell(test: &TestRun, folded: bool) -> R<String> {
 #[evscode::config]
 static TIME_DISPLAY_THRESHOLD: evscode::Config<u64> = 100u64;
 
-async fn render_out_cell(test: &TestRun, folded: bool) -> R<String> {
+/// If a solution uses more than the specified number of megabytes of memory, a note with its peak
+/// memory usage will be displayed. Set to 0 to always display the memory usage, or to a large value
+/// to never display it. Memory usage is only measured on Linux.
+#[evscode::config]
+static MEMORY_DISPLAY_THRESHOLD: evscode::Config<u64> = 64u64;
+
+async fn render_out_cell(test: &TestRun, diff: Option<&Diff>, folded: bool) -> R<String> {
 	let note_time = if test.outcome.time.as_millis() >= u128::from(TIME_DISPLAY_THRESHOLD.get())
 		|| test.outcome.verdict == Verdict::TimeLimitExceeded
 	{
@@ -130,42 +180,82 @@ async fn render_out_cell(test: &TestRun, folded: bool) -> R<String> {
 	} else {
 		None
 	};
+	let note_memory = match test.outcome.memory {
+		Some(memory)
+			if memory >= MEMORY_DISPLAY_THRESHOLD.get() * 1024 * 1024
+				|| test.outcome.verdict == Verdict::MemoryLimitExceeded =>
+		{
+			Some(util::fmt_memory_short(memory))
+		},
+		_ => None,
+	};
 	let note_verdict = match test.outcome.verdict {
 		Verdict::Accepted { .. } | Verdict::WrongAnswer | Verdict::IgnoredNoOut => None,
-		Verdict::RuntimeError => Some("RE"),
-		Verdict::TimeLimitExceeded => Some("TLE"),
+		Verdict::PresentationError => Some("PE".to_owned()),
+		Verdict::Scored { points } => Some(format!("{} pts", points)),
+		Verdict::CheckerFailure => Some("FAIL".to_owned()),
+		Verdict::RuntimeError => Some("RE".to_owned()),
+		Verdict::TimeLimitExceeded => Some("TLE".to_owned()),
+		Verdict::MemoryLimitExceeded => Some("MLE".to_owned()),
 	};
-	let notes =
-		vec![note_time.as_deref(), note_verdict].into_iter().filter_map(|o| o).collect::<Vec<_>>();
+	let note_diff = diff.and_then(|diff| diff.first.as_ref()).map(|first| first.to_string());
+	let notes = vec![
+		note_time.as_deref(),
+		note_memory.as_deref(),
+		note_verdict.as_deref(),
+		note_diff.as_deref(),
+	]
+	.into_iter()
+	.filter_map(|o| o)
+	.collect::<Vec<_>>();
 	let note = if notes.is_empty() { None } else { Some(notes.join("\n")) };
 	let attrs = [("data-raw", test.outcome.out.as_str())];
+	let transcript = test.outcome.transcript.as_deref().map(render_transcript);
 	let actions = [
 		(!HIDE_COPY.get(), ACTION_COPY),
 		(test.outcome.verdict == Verdict::WrongAnswer, ACTION_SET_ALT),
 		(test.outcome.verdict == Verdict::Accepted { alternative: true }, ACTION_DEL_ALT),
 		(true, ACTION_GDB),
 		(true, ACTION_RR),
+		(true, ACTION_JUDGE),
 	];
 	Ok(render_cell(
 		"output",
 		&attrs,
 		&actions,
 		Some(test.outcome.stderr.as_str()),
-		&test.outcome.out,
+		transcript.as_deref().unwrap_or(&test.outcome.out),
+		diff.map_or(&[][..], |diff| diff.out.as_slice()),
 		note.as_deref(),
+		test.outcome.checker_comment.as_deref(),
 		folded,
 	)
 	.await)
 }
 
-async fn render_desired_cell(test: &TestRun, folded: bool) -> R<String> {
+fn render_transcript(transcript: &[Message]) -> String {
+	transcript
+		.iter()
+		.flat_map(|message| {
+			let prefix = match message.sender {
+				Party::Solution => "> ",
+				Party::Interactor => "< ",
+			};
+			message.data.lines().map(move |line| format!("{}{}", prefix, line))
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+async fn render_desired_cell(test: &TestRun, diff: Option<&Diff>, folded: bool) -> R<String> {
 	let data = fs::read_to_string(&test.out_path).await.unwrap_or_default();
 	let attrs = [("data-raw", data.as_str())];
 	let actions = [
 		(test.outcome.verdict != Verdict::IgnoredNoOut && !HIDE_COPY.get(), ACTION_COPY),
 		(true, ACTION_EDIT),
 	];
-	Ok(render_cell("desired", &attrs, &actions, None, &data, None, folded).await)
+	let mismatches = diff.map_or(&[][..], |diff| diff.desired.as_slice());
+	Ok(render_cell("desired", &attrs, &actions, None, &data, mismatches, None, None, folded).await)
 }
 
 struct Action {
@@ -179,6 +269,8 @@ const ACTION_GDB: Action =
 	Action { onclick: "action_gdb()", icon: "skip_previous", hint: "Debug in GDB" };
 const ACTION_RR: Action =
 	Action { onclick: "action_rr()", icon: "fast_rewind", hint: "Debug in RR" };
+const ACTION_JUDGE: Action =
+	Action { onclick: "action_judge()", icon: "cloud_upload", hint: "Run on judge" };
 const ACTION_SET_ALT: Action =
 	Action { onclick: "action_setalt()", icon: "check", hint: "Mark as correct" };
 const ACTION_DEL_ALT: Action =
@@ -190,21 +282,24 @@ const ACTION_DEL_ALT: Action =
 #[evscode::config]
 static HIDE_COPY: evscode::Config<bool> = false;
 
+#[allow(clippy::too_many_arguments)]
 async fn render_cell(
 	class: &str,
 	attrs: &[(&str, &str)],
 	actions: &[(bool, Action)],
 	stderr: Option<&str>,
 	stdout: &str,
+	mismatches: &[usize],
 	note: Option<&str>,
+	comment: Option<&str>,
 	folded: bool,
 ) -> String
 {
 	if !folded {
-		render_cell_raw(class, attrs, actions, stderr, stdout, note).await
+		render_cell_raw(class, attrs, actions, stderr, stdout, mismatches, note, comment).await
 	} else {
 		let class = format!("{} folded", class);
-		render_cell_raw(&class, attrs, &[], None, "", None).await
+		render_cell_raw(&class, attrs, &[], None, "", &[], None, None).await
 	}
 }
 
@@ -216,13 +311,16 @@ const MIN_CELL_LINES: i64 = 2;
 #[evscode::config]
 static MAX_TEST_HEIGHT: evscode::Config<Option<u64>> = 720;
 
+#[allow(clippy::too_many_arguments)]
 async fn render_cell_raw(
 	class: &str,
 	attrs: &[(&str, &str)],
 	actions: &[(bool, Action)],
 	stderr: Option<&str>,
 	stdout: &str,
+	mismatches: &[usize],
 	note: Option<&str>,
+	comment: Option<&str>,
 ) -> String
 {
 	let actions = actions
@@ -240,11 +338,15 @@ async fn render_cell_raw(
 		if !SKILL_ACTIONS.is_proficient().await { "tutorialize" } else { "" },
 		actions.join("\n")
 	);
-	let note = note
-		.map_or(String::new(), |note| format!("<div class=\"note\">{}</div>", html_escape(note)));
+	let note = note.map_or(String::new(), |note| {
+		format!("<div class=\"note\">{}</div>", util::html_escape(note))
+	});
+	let comment = comment.map_or(String::new(), |comment| {
+		format!("<div class=\"comment\">{}</div>", util::html_escape_spaced(comment))
+	});
 	let lines = (stderr.as_ref().map_or(0, |stderr| lines(stderr)) + lines(stdout)) as i64;
 	let stderr = stderr.as_ref().map_or(String::new(), |stderr| {
-		format!("<div class=\"stderr\">{}</div>", html_escape_spaced(stderr.trim()))
+		format!("<div class=\"stderr\">{}</div>", util::html_escape_spaced(stderr.trim()))
 	});
 	let newline_fill = (0..max(MIN_CELL_LINES - lines + 1, 0)).map(|_| "<br/>").collect::<String>();
 	let max_test_height = MAX_TEST_HEIGHT.get();
@@ -255,41 +357,44 @@ async fn render_cell_raw(
 	};
 	let mut attr_html = String::new();
 	for (k, v) in attrs {
-		attr_html += &format!(" {}=\"{}\"", k, html_escape(v));
+		attr_html += &format!(" {}=\"{}\"", k, util::html_escape(v));
 	}
+	let stdout = if mismatches.is_empty() {
+		util::html_escape_spaced(stdout.trim())
+	} else {
+		html_highlight(stdout.trim(), mismatches)
+	};
 	let data = format!(
 		"<div class=\"data\" {}>{}{}{}</div>",
-		max_test_height,
-		stderr,
-		html_escape_spaced(stdout.trim()),
-		newline_fill
+		max_test_height, stderr, stdout, newline_fill
 	);
-	format!("<td class=\"cell {}\" {}>{}{}{}</td>", class, attr_html, actions, note, data)
+	format!(
+		"<td class=\"cell {}\" {}>{}{}{}{}</td>",
+		class,
+		attr_html,
+		actions,
+		note,
+		data,
+		comment
+	)
 }
 
-fn lines(s: &str) -> usize {
-	if !s.trim().is_empty() { s.trim().matches('\n').count() + 1 } else { 0 }
-}
-fn html_escape(s: &str) -> String {
-	translate(s, &[('&', "&amp;"), ('<', "&lt;"), ('>', "&gt;"), ('"', "&quot;"), ('\'', "&#39;")])
-}
-fn html_escape_spaced(s: &str) -> String {
-	translate(s, &[
-		('&', "&amp;"),
-		('<', "&lt;"),
-		('>', "&gt;"),
-		('"', "&quot;"),
-		('\'', "&#39;"),
-		('\n', "<br/>"),
-	])
-}
-fn translate(s: &str, table: &[(char, &str)]) -> String {
-	let mut buf = String::new();
-	for c in s.chars() {
-		match table.iter().find(|rule| rule.0 == c) {
-			Some(rule) => buf += rule.1,
-			_ => buf.push(c),
+fn html_highlight(text: &str, mismatches: &[usize]) -> String {
+	let mut html = String::new();
+	let mut last = 0;
+	for (index, token) in diff::tokenize(text).into_iter().enumerate() {
+		html += &util::html_escape_spaced(&text[last..token.start]);
+		if mismatches.binary_search(&index).is_ok() {
+			html += &format!("<span class=\"mismatch\">{}</span>", util::html_escape(token.text));
+		} else {
+			html += &util::html_escape(token.text);
 		}
+		last = token.end;
 	}
-	buf
+	html += &util::html_escape_spaced(&text[last..]);
+	html
+}
+
+fn lines(s: &str) -> usize {
+	if !s.trim().is_empty() { s.trim().matches('\n').count() + 1 } else { 0 }
 }
@@ -30,6 +30,7 @@ action_copy = make_action(ev => {
 });
 action_rr = make_action(ev => vscode.postMessage({ tag: "trigger_rr", in_path: ev.path_in }));
 action_gdb = make_action(ev => vscode.postMessage({ tag: "trigger_gdb", in_path: ev.path_in }));
+action_judge = make_action(ev => vscode.postMessage({ tag: "trigger_judge", in_path: ev.path_in }));
 action_setalt = make_action(ev => vscode.postMessage({ tag: "set_alt", in_path: ev.path_in, out: ev.row.dataset['raw_out'] }));
 action_delalt = make_action(ev => vscode.postMessage({ tag: "del_alt", in_path: ev.path_in }));
 action_edit = make_action(ev => {
@@ -96,6 +97,8 @@ window.addEventListener('message', event => {
 		scroll_to_wa();
 	} else if (message.tag === 'eval_resp') {
 		eval_finish(message);
+	} else if (message.tag === 'judge_resp') {
+		judge_show(message);
 	}
 });
 
@@ -151,6 +154,21 @@ function eval_finish(msg) {
 	}
 }
 
+function judge_show(msg) {
+	for (let row of Array.from(document.getElementsByClassName('row'))) {
+		if (row.dataset['path_in'] === msg.in_path) {
+			let output = class_kid(row, ['output']);
+			let judge = class_kid(output, ['judge']);
+			if (judge === undefined) {
+				judge = document.createElement('div');
+				judge.classList.add('judge');
+				output.appendChild(judge);
+			}
+			judge.innerText = `${msg.usage}\n${msg.output.trim()}`;
+		}
+	}
+}
+
 function autoexpand_textarea(tx) {
 	tx.setAttribute('style', `height: ${Math.max(86, tx.scrollHeight)}px; overflow-y: hidden;`);
 	tx.addEventListener('input', function () {
@@ -8,7 +8,7 @@ pub mod fs;
 pub mod path;
 pub mod tempfile;
 
-pub use tempfile::Tempfile;
+pub use tempfile::{Tempdir, Tempfile};
 
 pub fn fmt_time_short(t: &Duration) -> String {
 	let s = t.as_secs();
@@ -40,12 +40,23 @@ pub fn fmt_time_left(mut t: Duration) -> String {
 	s
 }
 
+pub fn fmt_memory_short(bytes: u64) -> String {
+	let kb = bytes / 1024;
+	format!("{}.{}MB", kb / 1024, kb % 1024 * 10 / 1024)
+}
+
 #[test]
 fn test_fmt_time() {
 	assert_eq!(fmt_time_short(&Duration::from_millis(2137)), "2.137s");
 	assert_eq!(fmt_time_short(&Duration::from_millis(42)), "0.042s");
 }
 
+#[test]
+fn test_fmt_memory() {
+	assert_eq!(fmt_memory_short(256 * 1024 * 1024), "256.0MB");
+	assert_eq!(fmt_memory_short(2860 * 1024), "2.7MB");
+}
+
 pub fn fmt_verb(verb: &'static str, path: impl MaybePath) -> String {
 	if let Some(path) = path.as_option_path() {
 		let file = match evscode::workspace_root() {
@@ -103,6 +114,32 @@ pub fn env(key: &'static str) -> R<String> {
 		.unwrap())
 }
 
+pub fn html_escape(s: &str) -> String {
+	translate(s, &[('&', "&amp;"), ('<', "&lt;"), ('>', "&gt;"), ('"', "&quot;"), ('\'', "&#39;")])
+}
+
+pub fn html_escape_spaced(s: &str) -> String {
+	translate(s, &[
+		('&', "&amp;"),
+		('<', "&lt;"),
+		('>', "&gt;"),
+		('"', "&quot;"),
+		('\'', "&#39;"),
+		('\n', "<br/>"),
+	])
+}
+
+fn translate(s: &str, table: &[(char, &str)]) -> String {
+	let mut buf = String::new();
+	for c in s.chars() {
+		match table.iter().find(|rule| rule.0 == c) {
+			Some(rule) => buf += rule.1,
+			_ => buf.push(c),
+		}
+	}
+	buf
+}
+
 pub fn html_material_icons() -> String {
 	match OS::query() {
 		// For whatever reason, bundled icons do not display on Windows.
@@ -23,6 +23,16 @@ pub async fn read_dir(path: &Path) -> R<Vec<Path>> {
 		.collect())
 }
 
+pub fn read_dir_sync(path: &Path) -> R<Vec<Path>> {
+	let files = node_sys::fs::readdir_sync(path.to_str().unwrap())
+		.map_err(|e| e.unchecked_into::<js_sys::Error>())?;
+	Ok(files
+		.values()
+		.into_iter()
+		.map(|file| path.join(file.unwrap().as_string().unwrap()))
+		.collect())
+}
+
 pub async fn read_to_string(path: &Path) -> R<String> {
 	let (tx, rx) = make_callback2();
 	node_sys::fs::read_file(
@@ -54,7 +64,14 @@ pub async fn remove_file(path: &Path) -> R<()> {
 }
 
 pub fn remove_file_sync(path: &Path) -> R<()> {
-	node_sys::fs::unlink_sync(path.to_str().unwrap());
+	node_sys::fs::unlink_sync(path.to_str().unwrap())
+		.map_err(|e| e.unchecked_into::<js_sys::Error>())?;
+	Ok(())
+}
+
+pub fn remove_dir_sync(path: &Path) -> R<()> {
+	node_sys::fs::rmdir_sync(path.to_str().unwrap())
+		.map_err(|e| e.unchecked_into::<js_sys::Error>())?;
 	Ok(())
 }
 
@@ -1,6 +1,11 @@
 use crate::util::{fs, path::Path, time_now};
 use evscode::R;
-use std::time::UNIX_EPOCH;
+use std::{
+	sync::atomic::{AtomicUsize, Ordering::SeqCst}, time::UNIX_EPOCH
+};
+
+/// Distinguishes temporary files created in the same microsecond, e.g. by parallel tests.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub struct Tempfile {
 	path: Path,
@@ -8,9 +13,7 @@ pub struct Tempfile {
 
 impl Tempfile {
 	pub async fn new(uniq_name: &str, data: impl AsRef<[u8]>) -> R<Tempfile> {
-		let id = time_now().duration_since(UNIX_EPOCH).unwrap().as_micros() % 1_000_000;
-		let path =
-			Path::from_native(node_sys::os::tmpdir()).join(format!("icie_{}_{}", uniq_name, id));
+		let path = unique_path(uniq_name);
 		fs::write(&path, data.as_ref()).await?;
 		Ok(Tempfile { path })
 	}
@@ -22,6 +25,39 @@ impl Tempfile {
 
 impl Drop for Tempfile {
 	fn drop(&mut self) {
-		fs::remove_file_sync(&self.path).unwrap();
+		let _ = fs::remove_file_sync(&self.path);
 	}
 }
+
+/// Temporary directory, removed together with the files inside it when dropped. Subdirectories are
+/// not removed, and prevent removing the directory itself.
+pub struct Tempdir {
+	path: Path,
+}
+
+impl Tempdir {
+	pub async fn new(uniq_name: &str) -> R<Tempdir> {
+		let path = unique_path(uniq_name);
+		fs::create_dir(&path).await?;
+		Ok(Tempdir { path })
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Drop for Tempdir {
+	fn drop(&mut self) {
+		for file in fs::read_dir_sync(&self.path).unwrap_or_default() {
+			let _ = fs::remove_file_sync(&file);
+		}
+		let _ = fs::remove_dir_sync(&self.path);
+	}
+}
+
+fn unique_path(uniq_name: &str) -> Path {
+	let id = time_now().duration_since(UNIX_EPOCH).unwrap().as_micros() % 1_000_000;
+	let count = COUNTER.fetch_add(1, SeqCst);
+	Path::from_native(node_sys::os::tmpdir()).join(format!("icie_{}_{}_{}", uniq_name, id, count))
+}
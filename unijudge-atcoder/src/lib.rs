@@ -1,13 +1,16 @@
 use async_trait::async_trait;
+use std::time::Duration;
 use unijudge::{
-	chrono::{FixedOffset, TimeZone}, debris::{self, Context, Document, Find}, http::{Client, Cookie}, reqwest::{
+	chrono::{DateTime, FixedOffset, TimeZone}, debris::{self, Context, Document, Find}, http::{Client, Cookie}, json, reqwest::{
 		header::{ORIGIN, REFERER}, StatusCode, Url
-	}, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Submission, TaskDetails, Verdict
+	}, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Standings, StandingsRow, Submission, TaskDetails, TaskResult, Verdict
 };
 
 #[derive(Debug)]
 pub struct AtCoder;
 
+const STANDINGS_LIMIT: usize = 100;
+
 #[derive(Debug)]
 pub struct Task {
 	contest: String,
@@ -73,10 +76,12 @@ impl unijudge::Backend for AtCoder {
 		{
 			Ok(resp) => resp,
 			// this is the worst way to indicate wrong password I have heard of
-			Err(ref e) if e.to_string().contains("Infinite redirect loop") => {
+			Err(Error::NetworkFailure(ref e))
+				if e.to_string().contains("Infinite redirect loop") =>
+			{
 				return Err(Error::WrongCredentials);
 			},
-			Err(e) => return Err(Error::NetworkFailure(e)),
+			Err(e) => return Err(e),
 		};
 		let doc = debris::Document::new(&resp.text().await?);
 		if doc.find("#main-container > div.row > div.alert.alert-success").is_ok() {
@@ -119,6 +124,18 @@ impl unijudge::Backend for AtCoder {
 					text[mark + 2..].to_owned(),
 				))
 			})?;
+		// The limits are shown as "Time Limit: 2 sec / Memory Limit: 1024 MB".
+		let limits = doc
+			.find_first("#main-container > .row > div > p")
+			.ok()
+			.map(|p| p.text().string())
+			.unwrap_or_default();
+		let mut limits = limits.split('/');
+		let time_limit = limits.next().and_then(unijudge::parse_time_limit);
+		let memory_limit = limits.next().and_then(unijudge::parse_memory_limit);
+		let interactive = doc
+			.find_all("#task-statement > .lang > .lang-en > .part h3")
+			.any(|h3| h3.text().string() == "Input and Output");
 		let parts = doc
 			.find_all("#task-statement > .lang > .lang-en > .part")
 			.filter(|node| {
@@ -205,6 +222,11 @@ impl unijudge::Backend for AtCoder {
 			examples,
 			statement: Some(statement.export()),
 			url: url.to_string(),
+			time_limit,
+			memory_limit,
+			input_file: None,
+			output_file: None,
+			interactive,
 		})
 	}
 
@@ -242,58 +264,7 @@ impl unijudge::Backend for AtCoder {
 		task: &Self::Task,
 	) -> Result<Vec<Submission>>
 	{
-		let url: Url =
-			format!("https://atcoder.jp/contests/{}/submissions/me", task.contest).parse()?;
-		let resp = session.get(url).send().await?;
-		let doc = debris::Document::new(&resp.text().await?);
-		Ok(doc
-			.find_all(".panel-submission tbody > tr")
-			.map(|row| {
-				let id = row.find(".submission-score")?.attr("data-id")?.string();
-				let status = row.find("td > span")?;
-				let status = status.text();
-				let (test_index, verdict) = match status.as_str().find(' ') {
-					Some(i) => (Some(&status.as_str()[..i]), Some(&status.as_str()[i + 1..])),
-					None if status.as_str().starts_with(char::is_numeric) => {
-						(Some(status.as_str()), None)
-					},
-					None => (None, Some(status.as_str())),
-				};
-				let verdict = match (verdict, test_index) {
-					(None, Some(index)) => Verdict::Pending { test: Some(index.to_owned()) },
-					(Some("WJ"), None) => Verdict::Pending { test: None },
-					(Some(verdict), _) => Verdict::Scored {
-						score: row.find(".submission-score")?.text().parse::<f64>()?,
-						max: None,
-						cause: match verdict {
-							"AC" => None,
-							"WA" => Some(RejectionCause::WrongAnswer),
-							"RE" => Some(RejectionCause::RuntimeError),
-							"TLE" => Some(RejectionCause::TimeLimitExceeded),
-							"CE" => Some(RejectionCause::CompilationError),
-							_ => {
-								return Err(status.error(format!(
-									"unrecognized AtCoder verdict {:?} [{:?} {:?}]",
-									status.as_str(),
-									verdict,
-									test_index
-								)));
-							},
-						},
-						test: None,
-					},
-					(None, None) => {
-						return Err(status.error(format!(
-							"unrecognized AtCoder verdict {:?} [{:?} {:?}]",
-							status.as_str(),
-							verdict,
-							test_index
-						)));
-					},
-				};
-				Ok(Submission { id, verdict })
-			})
-			.collect::<debris::Result<_>>()?)
+		self.contest_submissions(session, &task.contest).await
 	}
 
 	async fn task_submit(
@@ -327,6 +298,22 @@ impl unijudge::Backend for AtCoder {
 		format!("{}/submissions/{}", self.contest_url(&task.contest), id)
 	}
 
+	async fn submission_source(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		id: &str,
+	) -> Result<String>
+	{
+		let url: Url = self.submission_url(session, task, id).parse()?;
+		let resp = session.get(url.clone()).send().await?;
+		if *resp.url() != url {
+			return Err(Error::AccessDenied);
+		}
+		let doc = debris::Document::new(&resp.text().await?);
+		Ok(doc.find("#submission-code")?.text().string())
+	}
+
 	fn contest_id(&self, contest: &Self::Contest) -> String {
 		contest.clone()
 	}
@@ -431,12 +418,125 @@ impl unijudge::Backend for AtCoder {
 								"http://www.timeanddate.com/worldclock/fixedtime.html?iso=%Y%m%dT%H%M&p1=248",
 							)
 					})?;
-					Ok(ContestDetails { id, title, start })
+					Ok(ContestDetails { id, title, start: Some(start) })
 				})
 			})
 			.collect()
 	}
 
+	async fn contest_standings(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Standings>
+	{
+		let url: Url = format!("https://atcoder.jp/contests/{}/standings", contest).parse()?;
+		let resp = session.get(url.clone()).send().await?;
+		if *resp.url() != url {
+			return Err(Error::AccessDenied);
+		}
+		// The standings table is rendered by a script, which learns the logged in user from here.
+		let page = resp.text().await?;
+		let username = page
+			.find("userScreenName = \"")
+			.map(|i| &page[i + 18..])
+			.and_then(|rest| rest.find('"').map(|j| rest[..j].to_owned()))
+			.filter(|username| !username.is_empty());
+		let url: Url = format!("https://atcoder.jp/contests/{}/standings/json", contest).parse()?;
+		let resp = session.get(url).send().await?;
+		let api::Standings { task_info, standings_data } =
+			json::from_resp::<api::Standings>(resp, "/contests/{}/standings/json").await?;
+		let rows = standings_data
+			.into_iter()
+			.enumerate()
+			.filter(|(i, row)| {
+				*i < STANDINGS_LIMIT || Some(&row.user_screen_name) == username.as_ref()
+			})
+			.map(|(_, row)| StandingsRow {
+				rank: row.rank,
+				own: Some(&row.user_screen_name) == username.as_ref(),
+				participant: row.user_screen_name,
+				// Scores are multiplied by 100, so that partial points can be integers.
+				points: row.total_result.score / 100.,
+				penalty: Some(Duration::from_nanos(row.total_result.elapsed)),
+				results: task_info
+					.iter()
+					.map(|task| {
+						row.task_results.get(&task.task_screen_name).map(|result| TaskResult {
+							points: result.score / 100.,
+							rejected: result.penalty,
+						})
+					})
+					.collect(),
+			})
+			.collect();
+		Ok(Standings { tasks: task_info.into_iter().map(|task| task.assignment).collect(), rows })
+	}
+
+	async fn contest_submissions(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Submission>>
+	{
+		let url: Url = format!("https://atcoder.jp/contests/{}/submissions/me", contest).parse()?;
+		let resp = session.get(url).send().await?;
+		let doc = debris::Document::new(&resp.text().await?);
+		Ok(doc
+			.find_all(".panel-submission tbody > tr")
+			.map(|row| {
+				let id = row.find(".submission-score")?.attr("data-id")?.string();
+				let status = row.find("td > span")?;
+				let status = status.text();
+				let (test_index, verdict) = match status.as_str().find(' ') {
+					Some(i) => (Some(&status.as_str()[..i]), Some(&status.as_str()[i + 1..])),
+					None if status.as_str().starts_with(char::is_numeric) => {
+						(Some(status.as_str()), None)
+					},
+					None => (None, Some(status.as_str())),
+				};
+				let verdict = match (verdict, test_index) {
+					(None, Some(index)) => Verdict::Pending { test: Some(index.to_owned()) },
+					(Some("WJ"), None) => Verdict::Pending { test: None },
+					(Some(verdict), _) => Verdict::Scored {
+						score: row.find(".submission-score")?.text().parse::<f64>()?,
+						max: None,
+						cause: match verdict {
+							"AC" => None,
+							"WA" => Some(RejectionCause::WrongAnswer),
+							"RE" => Some(RejectionCause::RuntimeError),
+							"TLE" => Some(RejectionCause::TimeLimitExceeded),
+							"CE" => Some(RejectionCause::CompilationError),
+							_ => {
+								return Err(status.error(format!(
+									"unrecognized AtCoder verdict {:?} [{:?} {:?}]",
+									status.as_str(),
+									verdict,
+									test_index
+								)));
+							},
+						},
+						test: None,
+					},
+					(None, None) => {
+						return Err(status.error(format!(
+							"unrecognized AtCoder verdict {:?} [{:?} {:?}]",
+							status.as_str(),
+							verdict,
+							test_index
+						)));
+					},
+				};
+				let language = Some(row.find_nth("td", 3)?.text().string());
+				let time = row
+					.find("time")?
+					.text()
+					.map(|time| DateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%z"))?;
+				Ok(Submission { id, verdict, language, time: Some(time) })
+			})
+			.collect::<debris::Result<_>>()?)
+	}
+
 	fn name_short(&self) -> &'static str {
 		"atcoder"
 	}
@@ -454,3 +554,43 @@ impl AtCoder {
 		Ok(doc.find_first("[name=\"csrf_token\"]")?.attr("value")?.string())
 	}
 }
+
+mod api {
+
+	use serde::Deserialize;
+	use std::collections::HashMap;
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "PascalCase")]
+	pub struct Standings {
+		pub task_info: Vec<TaskInfo>,
+		pub standings_data: Vec<StandingsRow>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "PascalCase")]
+	pub struct TaskInfo {
+		pub assignment: String,
+		pub task_screen_name: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "PascalCase")]
+	pub struct StandingsRow {
+		pub rank: u64,
+		pub user_screen_name: String,
+		/// Results of the attempted tasks, keyed by task screen names.
+		pub task_results: HashMap<String, TaskResult>,
+		pub total_result: TaskResult,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "PascalCase")]
+	pub struct TaskResult {
+		pub score: f64,
+		/// Number of rejected submissions.
+		pub penalty: u64,
+		/// Time of the last accepted submission since the contest start, in nanoseconds.
+		pub elapsed: u64,
+	}
+}
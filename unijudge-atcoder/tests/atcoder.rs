@@ -0,0 +1,111 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Resource, Verdict};
+use unijudge_atcoder::{AtCoder, Task};
+
+const SITE: TestSite<AtCoder> =
+	TestSite { backend: &AtCoder, domain: "atcoder.jp", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+fn task() -> Task {
+	SITE.task("https://atcoder.jp/contests/abc100/tasks/abc100_a")
+}
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	let task_url = AtCoder.task_url(&session, &task()).unwrap();
+	assert_eq!(task_url, "https://atcoder.jp/contests/abc100/tasks/abc100_a");
+	for url in &["https://atcoder.jp/contests/agc041", "https://atcoder.jp/contests/agc041/tasks"] {
+		match SITE.resource(url) {
+			Resource::Contest(contest) => assert_eq!(contest, "agc041"),
+			Resource::Task(_) => panic!("expected a contest URL"),
+		}
+	}
+	assert!(AtCoder.deconstruct_url("https://atcoder.jp/ranking").is_err());
+	let foreign = AtCoder.deconstruct_url("https://codeforces.com/contest/1/problem/A").unwrap();
+	assert!(foreign.is_none());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let details = AtCoder.task_details(&SITE.session("task_details.json"), &task()).await.unwrap();
+	assert_eq!(details.id, "A");
+	assert_eq!(details.title, "Happy Birthday!");
+	assert_eq!(details.contest_id, "abc100");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(2)));
+	assert_eq!(details.memory_limit, Some(1024 * 1024 * 1024));
+	assert!(!details.interactive);
+	let examples = details.examples.unwrap();
+	assert_eq!(examples.len(), 2);
+	assert_eq!(examples[0].input, "5 4\n");
+	assert_eq!(examples[0].output, "Yay!\n");
+	assert_eq!(examples[1].input, "11 4\n");
+	assert_eq!(examples[1].output, ":(\n");
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = AtCoder.contests(&SITE.session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 1);
+	assert_eq!(contests[0].id, "abc152");
+	assert_eq!(contests[0].title, "AtCoder Beginner Contest 152");
+	assert_eq!(contests[0].start.unwrap().to_rfc3339(), "2020-01-19T21:00:00+09:00");
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let submissions =
+		AtCoder.task_submissions(&SITE.session("task_submissions.json"), &task()).await.unwrap();
+	assert_eq!(submissions.len(), 2);
+	assert_eq!(submissions[0].id, "9591312");
+	assert_eq!(submissions[0].verdict, Verdict::Scored {
+		score: 0.0,
+		max: None,
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+	assert_eq!(submissions[1].id, "9591299");
+	assert_eq!(submissions[1].verdict, Verdict::Scored {
+		score: 100.0,
+		max: None,
+		cause: None,
+		test: None
+	});
+	assert_eq!(submissions[1].language.as_deref(), Some("C++14 (GCC 5.4.1)"));
+	assert_eq!(submissions[1].time.unwrap().to_rfc3339(), "2020-01-15T18:01:43+09:00");
+}
+
+#[tokio::test]
+async fn test_submission_source() {
+	let source = AtCoder
+		.submission_source(&SITE.session("submission_source.json"), &task(), "9591299")
+		.await
+		.unwrap();
+	assert!(source.starts_with("#include <iostream>\n"));
+	assert!(source.contains("a <= 8 && b <= 8"));
+}
+
+#[tokio::test]
+async fn test_contest_submissions() {
+	let contest = "abc100".to_owned();
+	let submissions =
+		AtCoder.contest_submissions(&SITE.session("task_submissions.json"), &contest).await.unwrap();
+	let ids = submissions.iter().map(|submission| submission.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["9591312", "9591299"]);
+}
+
+#[tokio::test]
+async fn test_contest_standings() {
+	let contest = "abc100".to_owned();
+	let standings =
+		AtCoder.contest_standings(&SITE.session("contest_standings.json"), &contest).await.unwrap();
+	assert_eq!(standings.tasks, ["A", "B", "C", "D"]);
+	assert_eq!(standings.rows.len(), 2);
+	assert_eq!(standings.rows[0].participant, "tourist");
+	assert_eq!(standings.rows[0].points, 1000.);
+	assert!(!standings.rows[0].own);
+	let own = &standings.rows[1];
+	assert!(own.own);
+	assert_eq!(own.penalty, Some(std::time::Duration::from_secs(60)));
+	assert_eq!(own.results[0].as_ref().map(|result| result.points), Some(100.));
+	assert_eq!(own.results[1].as_ref().map(|result| result.rejected), Some(2));
+	assert!(own.results[2].is_none());
+}
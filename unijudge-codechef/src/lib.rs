@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::{future::Future, pin::Pin, sync::Mutex};
 use unijudge::{
-	debris::{Context, Document, Find}, http::{Client, Cookie}, json, reqwest::{StatusCode, Url}, ContestDetails, Error, Language, RejectionCause, Resource, Result, Statement, Submission, TaskDetails, Verdict
+	chrono::{FixedOffset, TimeZone}, debris::{Context, Document, Find}, http::{Client, Cookie}, json, reqwest::{StatusCode, Url}, ContestDetails, Error, Language, RejectionCause, Resource, Result, Statement, Submission, TaskDetails, Verdict
 };
 
 #[derive(Debug)]
@@ -165,6 +165,8 @@ impl unijudge::Backend for CodeChef {
 			"/api/contests/{}/problems/{}",
 		)
 		.await?;
+		let time_limit = resp.max_timelimit.as_deref().and_then(unijudge::parse_time_limit);
+		let interactive = resp.body.contains("### Interaction");
 		let statement = Some(self.prepare_statement(&resp.problem_name, resp.body));
 		Ok(TaskDetails {
 			id: task.task.clone(),
@@ -174,6 +176,12 @@ impl unijudge::Backend for CodeChef {
 			examples: None,
 			statement,
 			url: url.to_string(),
+			time_limit,
+			// CodeChef uses the same memory limit for all tasks and does not state it in the API.
+			memory_limit: None,
+			input_file: None,
+			output_file: None,
+			interactive,
 		})
 	}
 
@@ -246,7 +254,7 @@ impl unijudge::Backend for CodeChef {
 			// unnecessary. If I'll ever add a config option for network delays at least the most
 			// common case will be caught. I don't think I'll bother for other sites, since I only
 			// discovered this due to an error on my side.
-			return Err(Error::RateLimit);
+			return Err(Error::RateLimit { retry_after: None });
 		}
 		// If the code was submitted as a team, but tracking is done after logout, this will return
 		// an empty list every time. But I don't think this is a common situation so let's just
@@ -281,7 +289,16 @@ impl unijudge::Backend for CodeChef {
 						_ => Err(format!("unrecognized verdict {:?}", verdict)),
 					},
 				)?;
-				Ok(Submission { id, verdict })
+				let language = Some(row.find_nth("td", 6)?.text().string());
+				// Recent submissions are shown with a relative time like "2 min ago", which is not
+				// worth parsing.
+				let india_standard_time = FixedOffset::east(5 * 3600 + 30 * 60);
+				let time = row
+					.find_nth("td", 1)?
+					.text()
+					.map(|time| india_standard_time.datetime_from_str(time, "%I:%M %p %d/%m/%y"))
+					.ok();
+				Ok(Submission { id, verdict, language, time })
 			})
 			.collect()
 	}
@@ -320,7 +337,7 @@ impl unijudge::Backend for CodeChef {
 				inner: None,
 			})?)
 		} else {
-			Err(Error::RateLimit)
+			Err(Error::RateLimit { retry_after: None })
 		}
 	}
 
@@ -336,6 +353,22 @@ impl unijudge::Backend for CodeChef {
 		format!("https://www.codechef.com/submit/complete/{}", id)
 	}
 
+	async fn submission_source(
+		&self,
+		session: &Self::Session,
+		_task: &Self::Task,
+		id: &str,
+	) -> Result<String>
+	{
+		let url: Url = format!("https://www.codechef.com/viewplaintext/{}", id).parse()?;
+		let resp = session.client.get(url.clone()).send().await?;
+		if *resp.url() != url {
+			return Err(Error::AccessDenied);
+		}
+		let doc = Document::new(&resp.text().await?);
+		Ok(doc.find("pre")?.text().string())
+	}
+
 	fn contest_id(&self, contest: &Self::Contest) -> String {
 		contest.as_virt_symbol().to_owned()
 	}
@@ -394,7 +427,7 @@ impl unijudge::Backend for CodeChef {
 					.find_nth("td", 2)?
 					.attr("data-starttime")?
 					.map(|start_time| unijudge::chrono::DateTime::parse_from_rfc3339(start_time))?;
-				Ok(ContestDetails { id, title, start })
+				Ok(ContestDetails { id, title, start: Some(start) })
 			})
 			.collect()
 	}
@@ -604,6 +637,9 @@ mod api {
 		/// Task statement in Markdown with HTML tags and MathJax $ tags.
 		/// Contains example tests.
 		pub body: String,
+		/// Time limit in seconds, like "1" or "2.5".
+		#[serde(default)]
+		pub max_timelimit: Option<String>,
 	}
 
 	#[derive(Debug, Deserialize)]
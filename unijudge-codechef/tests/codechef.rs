@@ -0,0 +1,78 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Resource, Verdict};
+use unijudge_codechef::CodeChef;
+
+const SITE: TestSite<CodeChef> = TestSite {
+	backend: &CodeChef,
+	domain: "www.codechef.com",
+	manifest_dir: env!("CARGO_MANIFEST_DIR"),
+};
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	let practice = SITE.task("https://www.codechef.com/problems/FLOW001");
+	let practice_url = CodeChef.task_url(&session, &practice).unwrap();
+	assert_eq!(practice_url, "https://www.codechef.com/PRACTICE/problems/FLOW001");
+	let normal = SITE.task("https://www.codechef.com/COOK113A/problems/MINMAXEQ");
+	let normal_url = CodeChef.task_url(&session, &normal).unwrap();
+	assert_eq!(normal_url, "https://www.codechef.com/COOK113A/problems/MINMAXEQ");
+	match SITE.resource("https://www.codechef.com/COOK113A") {
+		Resource::Contest(contest) => assert_eq!(CodeChef.contest_id(&contest), "COOK113A"),
+		Resource::Task(_) => panic!("expected a contest URL"),
+	}
+	assert!(CodeChef.deconstruct_url("https://www.codechef.com/users/tester/rating").is_err());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = SITE.task("https://www.codechef.com/problems/FLOW001");
+	let details = CodeChef.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "FLOW001");
+	assert_eq!(details.title, "Add Two Numbers");
+	assert_eq!(details.contest_id, "PRACTICE");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(1)));
+	assert!(!details.interactive);
+	assert!(details.statement.is_some());
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = CodeChef.contests(&SITE.session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 2);
+	assert_eq!(CodeChef.contest_id(&contests[0].id), "COOK114");
+	assert_eq!(contests[0].title, "January Cook-Off 2020");
+	assert_eq!(contests[0].start.unwrap().to_rfc3339(), "2020-01-19T21:30:00+05:30");
+	assert_eq!(CodeChef.contest_id(&contests[1].id), "LTIME80");
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = SITE.session("task_submissions.json");
+	let auth = CodeChef
+		.auth_deserialize(r#"{"username":"tester","c_sess":{"cookie":"SESS93b6=af3c"}}"#)
+		.unwrap();
+	CodeChef.auth_restore(&session, &auth).await.unwrap();
+	let task = SITE.task("https://www.codechef.com/problems/FLOW001");
+	let submissions = CodeChef.task_submissions(&session, &task).await.unwrap();
+	assert_eq!(submissions.len(), 2);
+	assert_eq!(submissions[0].id, "28890137");
+	assert_eq!(submissions[0].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::TimeLimitExceeded),
+		test: None
+	});
+	assert_eq!(submissions[1].id, "28890102");
+	assert_eq!(submissions[1].verdict, Verdict::Accepted);
+	assert_eq!(submissions[1].language.as_deref(), Some("C++14"));
+	assert_eq!(submissions[1].time.unwrap().to_rfc3339(), "2020-01-15T20:11:00+05:30");
+}
+
+#[tokio::test]
+async fn test_submission_source() {
+	let task = SITE.task("https://www.codechef.com/problems/FLOW001");
+	let source = CodeChef
+		.submission_source(&SITE.session("submission_source.json"), &task, "28890102")
+		.await
+		.unwrap();
+	assert!(source.starts_with("#include <bits/stdc++.h>\n"));
+	assert!(source.contains("cin >> n;"));
+}
@@ -2,16 +2,18 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::{sync::Mutex, time::Duration};
 use unijudge::{
-	chrono::{FixedOffset, TimeZone}, debris::{Context, Document, Find}, http::{Client, Cookie}, log::debug, reqwest::{
+	chrono::{FixedOffset, TimeZone}, debris::{Context, Document, Find}, http::{Client, Cookie}, json, log::debug, reqwest::{
 		self, header::{ORIGIN, REFERER}, Url
-	}, Backend, ContestDetails, Error, Example, Language, Resource, Result, Statement, Submission, TaskDetails
+	}, Backend, ContestDetails, Error, Example, Invocation, Language, Resource, Result, Standings, StandingsRow, Statement, Submission, TaskDetails, TaskResult
 };
 
 #[derive(Debug)]
 pub struct Codeforces;
 
+const STANDINGS_LIMIT: usize = 100;
+
 #[derive(Debug)]
 pub enum TaskID {
 	Normal(String),
@@ -39,6 +41,8 @@ pub struct Task {
 pub struct Session {
 	client: Client,
 	username: Mutex<Option<String>>,
+	/// CSRF token from the custom invocation page, reused when polling for the results.
+	custom_test_csrf: Mutex<Option<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -98,7 +102,7 @@ impl unijudge::Backend for Codeforces {
 	}
 
 	fn connect(&self, client: Client, _: &str) -> Self::Session {
-		Session { client, username: Mutex::new(None) }
+		Session { client, username: Mutex::new(None), custom_test_csrf: Mutex::new(None) }
 	}
 
 	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
@@ -190,10 +194,10 @@ impl unijudge::Backend for Codeforces {
 				.get(format!("https://codeforces.com{}", href).parse()?)
 				.send()
 				.await?;
-			let pdf = resp.bytes().await?.as_ref().to_owned();
+			let pdf = resp.bytes().await?;
 			ExtractedStatement::from_pdf(self, session, task, pdf).await?
 		} else if resp.headers()["Content-Type"] == "application/pdf;charset=UTF-8" {
-			let pdf = resp.bytes().await?.as_ref().to_owned();
+			let pdf = resp.bytes().await?;
 			ExtractedStatement::from_pdf(self, session, task, pdf).await?
 		} else {
 			let doc = unijudge::debris::Document::new(&resp.text().await?);
@@ -207,6 +211,11 @@ impl unijudge::Backend for Codeforces {
 			examples: statement.examples,
 			statement: Some(statement.statement),
 			url: url.to_string(),
+			time_limit: statement.time_limit,
+			memory_limit: statement.memory_limit,
+			input_file: statement.input_file,
+			output_file: statement.output_file,
+			interactive: statement.interactive,
 		})
 	}
 
@@ -243,67 +252,7 @@ impl unijudge::Backend for Codeforces {
 		task: &Self::Task,
 	) -> Result<Vec<Submission>>
 	{
-		debug!("unijudge_codeforces.Codeforces.task_submissions");
-		let url = match &task.contest.source {
-			Source::Contest | Source::Gym => self.task_contest_url(task)?.join("my")?,
-			Source::Problemset => {
-				format!("https://codeforces.com/submissions/{}", session.req_user()?).parse()?
-			},
-			Source::Group { group } => {
-				format!("https://codeforces.com/group/{}/contest/{}/my", group, task.contest.id)
-					.parse()?
-			},
-		};
-		let resp = session.client.get(url).send().await?;
-		debug!("unijudge_codeforces.Codeforces.task_submissions received submission list");
-		let doc = unijudge::debris::Document::new(&resp.text().await?);
-		Ok(doc
-			.find_all("[data-submission-id]")
-			.map(|node| {
-				let kids = node.find_all("td").collect::<Vec<_>>();
-				let id = kids[0].child(1)?.text().string();
-				let verdict = if kids[5].text() == "In queue" {
-					Verdict::InQueue
-				} else if kids[5].text() == "Running" {
-					Verdict::TestingStart
-				} else {
-					let verdict_span = kids[5].find_first("span")?;
-					let verdict_tag = verdict_span.attr("submissionverdict")?;
-					match verdict_tag.as_str() {
-						"OK" => Verdict::Accepted,
-						"WRONG_ANSWER" => Verdict::WrongAnswer(TestIndex::scrap(verdict_span)?),
-						"COMPILATION_ERROR" => Verdict::CompilationError,
-						"TESTING" => Verdict::Testing(TestIndex::scrap(verdict_span)?),
-						"RUNTIME_ERROR" => Verdict::RuntimeError(TestIndex::scrap(verdict_span)?),
-						"TIME_LIMIT_EXCEEDED" => {
-							Verdict::TimeLimitExceeded(TestIndex::scrap(verdict_span)?)
-						},
-						"MEMORY_LIMIT_EXCEEDED" => {
-							Verdict::MemoryLimitExceeded(TestIndex::scrap(verdict_span)?)
-						},
-						"PARTIAL" => Verdict::Partial(
-							verdict_span.find(".verdict-format-points")?.text().parse()?,
-						),
-						"SKIPPED" => Verdict::Skipped,
-						"CHALLENGED" => Verdict::Hacked,
-						"FAILED" => Verdict::JudgementFailed,
-						"IDLENESS_LIMIT_EXCEEDED" => {
-							Verdict::IdlenessLimitExceeded(TestIndex::scrap(verdict_span)?)
-						},
-						"CRASHED" => Verdict::DenialOfJudgement,
-						// PE is present as a verdict filter, but not as an actual verdict.
-						// SV/IPF seem to be an actual verdicts, but I can't find an example.
-						_ => {
-							return Err(Error::from(
-								verdict_span.error("unrecognized verdict tag"),
-							));
-						},
-					}
-				}
-				.to_unijudge();
-				Ok(Submission { id, verdict })
-			})
-			.collect::<Result<Vec<_>>>()?)
+		self.fetch_submissions(session, &task.contest, Some(self.resolve_task_id(task))).await
 	}
 
 	async fn task_submit(
@@ -349,12 +298,112 @@ impl unijudge::Backend for Codeforces {
 		Ok(self.task_submissions(session, task).await?[0].id.to_string())
 	}
 
+	async fn invocation_start(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+		input: &str,
+	) -> Result<String>
+	{
+		debug!("unijudge_codeforces.Codeforces.invocation_start language = {:?}", language);
+		let url = self.task_contest_url(task)?.join("customtest")?;
+		let resp1 = session.client.get(url.clone()).send().await?;
+		if *resp1.url() != url {
+			debug!("unijudge_codeforces.Codeforces.invocation_start was redirected to login page");
+			return Err(Error::AccessDenied);
+		}
+		let csrf = {
+			let doc = unijudge::debris::Document::new(&resp1.text().await?);
+			doc.find_first("[name=\"csrf_token\"]")?.attr("value")?.string()
+		};
+		*session.custom_test_csrf.lock().map_err(|_| Error::StateCorruption)? = Some(csrf.clone());
+		let resp2 = session
+			.client
+			.post("https://codeforces.com/data/customtest".parse()?)
+			.header(ORIGIN, "https://codeforces.com")
+			.header(REFERER, url.as_str())
+			.form(&[
+				("action", "submitSourceCode"),
+				("csrf_token", csrf.as_str()),
+				("programTypeId", language.id.as_str()),
+				("source", code),
+				("tabSize", "4"),
+				("input", input),
+			])
+			.send()
+			.await?;
+		let submit = json::from_resp::<api::CustomTestSubmit>(resp2, "/data/customtest").await?;
+		Ok(submit.custom_test_submit_id)
+	}
+
+	async fn invocation_result(
+		&self,
+		session: &Self::Session,
+		_task: &Self::Task,
+		id: &str,
+	) -> Result<Option<Invocation>>
+	{
+		let csrf = session.custom_test_csrf.lock().map_err(|_| Error::StateCorruption)?.clone();
+		let csrf = match csrf {
+			Some(csrf) => csrf,
+			None => self.fetch_csrf(session).await?,
+		};
+		let resp = session
+			.client
+			.post("https://codeforces.com/data/customtest".parse()?)
+			.header(ORIGIN, "https://codeforces.com")
+			.form(&[
+				("action", "getVerdict"),
+				("csrf_token", csrf.as_str()),
+				("customTestSubmitId", id),
+			])
+			.send()
+			.await?;
+		let verdict = json::from_resp::<api::CustomTestVerdict>(resp, "/data/customtest").await?;
+		let (output, stat) = match (verdict.output, verdict.stat) {
+			(Some(output), Some(stat)) => (output, stat),
+			_ => return Ok(None),
+		};
+		// The statistics end with a line like "Used: 15 ms, 3580 KB", preceded by the exit code.
+		let usage = stat.rfind("Used:").map(|i| stat[i + 5..].trim());
+		let time = usage
+			.and_then(|usage| usage.split(' ').next()?.parse().ok())
+			.map(Duration::from_millis);
+		let memory =
+			usage.and_then(|usage| usage.split(", ").nth(1)).and_then(unijudge::parse_memory_limit);
+		Ok(Some(Invocation { output, time, memory }))
+	}
+
 	fn task_url(&self, _sess: &Self::Session, task: &Self::Task) -> Result<String> {
 		Ok(self.xtask_url(task)?.into_string())
 	}
 
 	fn submission_url(&self, _sess: &Self::Session, task: &Self::Task, id: &str) -> String {
-		format!("{}/submission/{}", self.contest_url(&task.contest), id)
+		match &task.contest.source {
+			Source::Problemset => format!(
+				"https://codeforces.com/problemset/submission/{}/{}",
+				task.contest.id, id
+			),
+			_ => format!("{}submission/{}", self.contest_url(&task.contest), id),
+		}
+	}
+
+	async fn submission_source(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		id: &str,
+	) -> Result<String>
+	{
+		let url: Url = self.submission_url(session, task, id).parse()?;
+		let resp = session.client.get(url.clone()).send().await?;
+		if *resp.url() != url {
+			return Err(Error::AccessDenied);
+		}
+		let doc = Document::new(&resp.text().await?);
+		Ok(doc.find("#program-source-text")?.text().string())
 	}
 
 	fn contest_id(&self, contest: &Self::Contest) -> String {
@@ -432,11 +481,72 @@ impl unijudge::Backend for Codeforces {
 						"https://www.timeanddate.com/worldclock/fixedtime.html?day=%e&month=%m&year=%Y&hour=%k&min=%M&sec=%S&p1=166",
 					)
 				})?;
-				Ok(ContestDetails { id, title, start })
+				Ok(ContestDetails { id, title, start: Some(start) })
 			})
 			.collect()
 	}
 
+	async fn contest_standings(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Standings>
+	{
+		match contest.source {
+			Source::Contest | Source::Gym => (),
+			Source::Problemset | Source::Group { .. } => return Err(Error::UnsupportedFeature),
+		}
+		let username = session.username.lock().map_err(|_| Error::StateCorruption)?.clone();
+		let count = STANDINGS_LIMIT.to_string();
+		let query = [("from", "1"), ("count", count.as_str())];
+		let mut standings = self.api_standings(session, contest, &query).await?;
+		if let Some(username) = &username {
+			if standings.rows.iter().all(|row| !row.party.has_member(username)) {
+				let query = [("handles", username.as_str())];
+				let own = self.api_standings(session, contest, &query).await?;
+				standings.rows.extend(own.rows);
+			}
+		}
+		let icpc = standings.contest.kind == "ICPC";
+		Ok(Standings {
+			tasks: standings.problems.into_iter().map(|problem| problem.index).collect(),
+			rows: standings
+				.rows
+				.into_iter()
+				.map(|row| StandingsRow {
+					rank: row.rank,
+					own: username.as_ref().map_or(false, |username| row.party.has_member(username)),
+					participant: row.party.name(),
+					points: row.points,
+					penalty: if icpc { Some(Duration::from_secs(row.penalty * 60)) } else { None },
+					results: row
+						.problem_results
+						.into_iter()
+						.map(|result| {
+							if result.points > 0. || result.rejected_attempt_count > 0 {
+								Some(TaskResult {
+									points: result.points,
+									rejected: result.rejected_attempt_count,
+								})
+							} else {
+								None
+							}
+						})
+						.collect(),
+				})
+				.collect(),
+		})
+	}
+
+	async fn contest_submissions(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Submission>>
+	{
+		self.fetch_submissions(session, contest, None).await
+	}
+
 	fn name_short(&self) -> &'static str {
 		"codeforces"
 	}
@@ -475,6 +585,89 @@ impl Codeforces {
 			.collect()
 	}
 
+	/// Fetches submissions to the contest, or only to one of its tasks if the symbol is given.
+	async fn fetch_submissions(
+		&self,
+		session: &Session,
+		contest: &Contest,
+		task: Option<&str>,
+	) -> Result<Vec<Submission>>
+	{
+		debug!("unijudge_codeforces.Codeforces.fetch_submissions");
+		let moscow_standard_time = FixedOffset::east(3 * 3600);
+		let url = match &contest.source {
+			Source::Contest | Source::Gym => self.contest_url(contest).parse::<Url>()?.join("my")?,
+			Source::Problemset => {
+				format!("https://codeforces.com/submissions/{}", session.req_user()?).parse()?
+			},
+			Source::Group { group } => {
+				format!("https://codeforces.com/group/{}/contest/{}/my", group, contest.id)
+					.parse()?
+			},
+		};
+		let resp = session.client.get(url).send().await?;
+		debug!("unijudge_codeforces.Codeforces.fetch_submissions received submission list");
+		let doc = unijudge::debris::Document::new(&resp.text().await?);
+		Ok(doc
+			.find_all("[data-submission-id]")
+			.map(|node| {
+				let kids = node.find_all("td").collect::<Vec<_>>();
+				if let Some(task) = task {
+					let href = kids[3].find_first("a")?.attr("href")?;
+					if !links_to_task(href.as_str(), &contest.id, task) {
+						return Ok(None);
+					}
+				}
+				let id = kids[0].child(1)?.text().string();
+				let verdict = if kids[5].text() == "In queue" {
+					Verdict::InQueue
+				} else if kids[5].text() == "Running" {
+					Verdict::TestingStart
+				} else {
+					let verdict_span = kids[5].find_first("span")?;
+					let verdict_tag = verdict_span.attr("submissionverdict")?;
+					match verdict_tag.as_str() {
+						"OK" => Verdict::Accepted,
+						"WRONG_ANSWER" => Verdict::WrongAnswer(TestIndex::scrap(verdict_span)?),
+						"COMPILATION_ERROR" => Verdict::CompilationError,
+						"TESTING" => Verdict::Testing(TestIndex::scrap(verdict_span)?),
+						"RUNTIME_ERROR" => Verdict::RuntimeError(TestIndex::scrap(verdict_span)?),
+						"TIME_LIMIT_EXCEEDED" => {
+							Verdict::TimeLimitExceeded(TestIndex::scrap(verdict_span)?)
+						},
+						"MEMORY_LIMIT_EXCEEDED" => {
+							Verdict::MemoryLimitExceeded(TestIndex::scrap(verdict_span)?)
+						},
+						"PARTIAL" => Verdict::Partial(
+							verdict_span.find(".verdict-format-points")?.text().parse()?,
+						),
+						"SKIPPED" => Verdict::Skipped,
+						"CHALLENGED" => Verdict::Hacked,
+						"FAILED" => Verdict::JudgementFailed,
+						"IDLENESS_LIMIT_EXCEEDED" => {
+							Verdict::IdlenessLimitExceeded(TestIndex::scrap(verdict_span)?)
+						},
+						"CRASHED" => Verdict::DenialOfJudgement,
+						// PE is present as a verdict filter, but not as an actual verdict.
+						// SV/IPF seem to be an actual verdicts, but I can't find an example.
+						_ => {
+							return Err(Error::from(
+								verdict_span.error("unrecognized verdict tag"),
+							));
+						},
+					}
+				}
+				.to_unijudge();
+				let language = Some(kids[4].text().string());
+				let time = kids[1]
+					.text()
+					.map(|time| moscow_standard_time.datetime_from_str(time, "%b/%d/%Y %H:%M"))?;
+				Ok(Some(Submission { id, verdict, language, time: Some(time) }))
+			})
+			.filter_map(Result::transpose)
+			.collect::<Result<Vec<_>>>()?)
+	}
+
 	fn resolve_task_id<'a>(&self, task: &'a Task) -> &'a str {
 		match &task.task {
 			TaskID::Normal(task_id) => task_id.as_str(),
@@ -517,6 +710,33 @@ impl Codeforces {
 		}
 	}
 
+	async fn api_standings(
+		&self,
+		session: &Session,
+		contest: &Contest,
+		query: &[(&str, &str)],
+	) -> Result<api::Standings>
+	{
+		let url: Url = "https://codeforces.com/api/contest.standings".parse()?;
+		let resp = session
+			.client
+			.get(url)
+			.query(&[("contestId", contest.id.as_str()), ("showUnofficial", "false")])
+			.query(query)
+			.send()
+			.await?;
+		let resp = json::from_resp::<api::Response<api::Standings>>(resp, "/api/contest.standings")
+			.await?;
+		match resp.result {
+			Some(standings) => Ok(standings),
+			// The API reports contests that have not started yet as errors.
+			None if resp.comment.as_ref().map_or(false, |c| c.contains("has not started")) => {
+				Err(Error::NotYetStarted)
+			},
+			None => Err(Error::AccessDenied),
+		}
+	}
+
 	async fn fetch_csrf(&self, session: &Session) -> Result<String> {
 		debug!("unijudge_codeforces.Codeforces.fetch_csrf fetching csrf");
 		let resp = session.client.get("https://codeforces.com".parse()?).send().await?;
@@ -531,6 +751,11 @@ struct ExtractedStatement {
 	title: String,
 	examples: Option<Vec<Example>>,
 	statement: Statement,
+	time_limit: Option<Duration>,
+	memory_limit: Option<u64>,
+	input_file: Option<String>,
+	output_file: Option<String>,
+	interactive: bool,
 }
 impl ExtractedStatement {
 	fn from_html(doc: Document) -> Result<ExtractedStatement> {
@@ -553,6 +778,21 @@ impl ExtractedStatement {
 				})
 				.collect::<Result<_>>()?,
 		);
+		// The limits and file names are not necessary to open the task, so a missing header line
+		// should not make the whole statement unusable.
+		let header =
+			|selector: &'static str| doc.find(selector).ok().map(|node| node.text().string());
+		let time_limit = header(".problem-statement > .header > .time-limit")
+			.and_then(|text| unijudge::parse_time_limit(&text));
+		let memory_limit = header(".problem-statement > .header > .memory-limit")
+			.and_then(|text| unijudge::parse_memory_limit(&text));
+		let input_file = header(".problem-statement > .header > .input-file")
+			.and_then(|text| io_file(text, "input"));
+		let output_file = header(".problem-statement > .header > .output-file")
+			.and_then(|text| io_file(text, "output"));
+		let interactive = doc
+			.find_all(".problem-statement .section-title")
+			.any(|title| title.text().string() == "Interaction");
 		let mut statement = unijudge::statement::Rewrite::start(doc);
 		statement.fix_hide(|v| {
 			if let unijudge::scraper::Node::Element(v) = v.value() {
@@ -589,7 +829,17 @@ impl ExtractedStatement {
 				}
 			}
 		});
-		Ok(ExtractedStatement { symbol, title, examples, statement: statement.export() })
+		Ok(ExtractedStatement {
+			symbol,
+			title,
+			examples,
+			statement: statement.export(),
+			time_limit,
+			memory_limit,
+			input_file,
+			output_file,
+			interactive,
+		})
 	}
 
 	async fn from_pdf(
@@ -615,10 +865,26 @@ impl ExtractedStatement {
 			title: task.title,
 			examples: None,
 			statement: Statement::PDF { pdf },
+			time_limit: None,
+			memory_limit: None,
+			input_file: None,
+			output_file: None,
+			interactive: false,
 		})
 	}
 }
 
+/// Extracts the file name from texts like "inputstandard input" or "outputoutput.txt", which is
+/// how the property title and value are concatenated in the statement header.
+fn io_file(text: String, property: &str) -> Option<String> {
+	let value = text.trim().trim_start_matches(property).trim();
+	if value.is_empty() || value.starts_with("standard") {
+		None
+	} else {
+		Some(value.to_owned())
+	}
+}
+
 impl Session {
 	fn req_user(&self) -> Result<String> {
 		self.username.lock().map_err(|_| Error::StateCorruption)?.clone().ok_or(Error::AccessDenied)
@@ -710,3 +976,108 @@ impl Verdict {
 		}
 	}
 }
+
+/// Checks whether a problem link from a submission list points to the given task. The link format
+/// depends on where the list is, but all of them contain the contest ID and end with the symbol.
+fn links_to_task(href: &str, contest: &str, task: &str) -> bool {
+	let segments = href.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+	segments.last() == Some(&task) && segments.contains(&contest)
+}
+
+mod api {
+
+	use serde::Deserialize;
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct CustomTestSubmit {
+		pub custom_test_submit_id: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Response<T> {
+		pub status: String,
+		#[serde(default)]
+		pub comment: Option<String>,
+		#[serde(default)]
+		pub result: Option<T>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Standings {
+		pub contest: StandingsContest,
+		pub problems: Vec<Problem>,
+		pub rows: Vec<RanklistRow>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct StandingsContest {
+		/// Either "CF", "IOI" or "ICPC".
+		#[serde(rename = "type")]
+		pub kind: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Problem {
+		pub index: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct RanklistRow {
+		pub party: Party,
+		pub rank: u64,
+		pub points: f64,
+		/// Penalty in minutes, only meaningful in ICPC contests.
+		pub penalty: u64,
+		pub problem_results: Vec<ProblemResult>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct Party {
+		pub members: Vec<Member>,
+		#[serde(default)]
+		pub team_name: Option<String>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Member {
+		pub handle: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct ProblemResult {
+		pub points: f64,
+		pub rejected_attempt_count: u64,
+	}
+
+	impl Party {
+		pub fn has_member(&self, handle: &str) -> bool {
+			self.members.iter().any(|member| member.handle.eq_ignore_ascii_case(handle))
+		}
+
+		pub fn name(&self) -> String {
+			match &self.team_name {
+				Some(team_name) => team_name.clone(),
+				None => self
+					.members
+					.iter()
+					.map(|member| member.handle.as_str())
+					.collect::<Vec<_>>()
+					.join(", "),
+			}
+		}
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct CustomTestVerdict {
+		/// Program output, or compilation errors. Missing until the run finishes.
+		#[serde(default)]
+		pub output: Option<String>,
+		/// Exit code, time and memory usage. Missing until the run finishes.
+		#[serde(default)]
+		pub stat: Option<String>,
+	}
+}
@@ -0,0 +1,146 @@
+use unijudge::{testing::TestSite, Backend, Error, Language, RejectionCause, Verdict};
+use unijudge_codeforces::Codeforces;
+
+const SITE: TestSite<Codeforces> = TestSite {
+	backend: &Codeforces,
+	domain: "codeforces.com",
+	manifest_dir: env!("CARGO_MANIFEST_DIR"),
+};
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	for url in &[
+		"https://codeforces.com/contest/1294/problem/C",
+		"https://codeforces.com/gym/102433/problem/B",
+		"https://codeforces.com/problemset/problem/4/A",
+		"https://codeforces.com/group/9Vh3bR0Zpd/contest/262934/problem/A/",
+	] {
+		assert_eq!(Codeforces.task_url(&session, &SITE.task(url)).unwrap(), *url);
+	}
+	assert_eq!(Codeforces.contest_id(&SITE.contest("https://codeforces.com/contest/1294")), "1294");
+	assert_eq!(Codeforces.contest_id(&SITE.contest("https://codeforces.com/gym/102433")), "gym102433");
+	let group = SITE.contest("https://codeforces.com/group/9Vh3bR0Zpd/contest/262934");
+	assert_eq!(Codeforces.contest_id(&group), "group9Vh3bR0Zpd262934");
+	assert!(Codeforces.deconstruct_url("https://codeforces.com/blog/entry/73105").is_err());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = SITE.task("https://codeforces.com/problemset/problem/4/A");
+	let details = Codeforces.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "A");
+	assert_eq!(details.title, "Watermelon");
+	assert_eq!(details.contest_id, "problemset 4");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(1)));
+	assert_eq!(details.memory_limit, Some(64 * 1024 * 1024));
+	assert_eq!(details.input_file, None);
+	assert_eq!(details.output_file, None);
+	assert!(!details.interactive);
+	let examples = details.examples.unwrap();
+	assert_eq!(examples.len(), 1);
+	assert_eq!(examples[0].input, "8\n");
+	assert_eq!(examples[0].output, "YES\n");
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = Codeforces.contests(&SITE.session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 2);
+	assert_eq!(Codeforces.contest_id(&contests[0].id), "1295");
+	assert_eq!(contests[0].title, "Educational Codeforces Round 81 (Rated for Div. 2)");
+	assert_eq!(contests[0].start.unwrap().to_rfc3339(), "2020-01-29T17:35:00+03:00");
+	assert_eq!(Codeforces.contest_id(&contests[1].id), "1291");
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let task = SITE.task("https://codeforces.com/contest/1294/problem/C");
+	let submissions =
+		Codeforces.task_submissions(&SITE.session("task_submissions.json"), &task).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "69626171");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "69625934");
+	assert_eq!(submissions[1].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: Some("test 3".to_owned())
+	});
+	assert_eq!(submissions[2].id, "69625512");
+	assert_eq!(submissions[2].verdict, Verdict::Accepted);
+	assert_eq!(submissions[2].language.as_deref(), Some("GNU C++17"));
+	assert_eq!(submissions[2].time.unwrap().to_rfc3339(), "2020-01-22T18:36:00+03:00");
+}
+
+#[tokio::test]
+async fn test_task_submissions_other_task() {
+	let task = SITE.task("https://codeforces.com/contest/1294/problem/B");
+	let submissions =
+		Codeforces.task_submissions(&SITE.session("task_submissions.json"), &task).await.unwrap();
+	assert!(submissions.is_empty());
+}
+
+#[tokio::test]
+async fn test_submission_source() {
+	let task = SITE.task("https://codeforces.com/contest/1294/problem/C");
+	let source = Codeforces
+		.submission_source(&SITE.session("submission_source.json"), &task, "69625512")
+		.await
+		.unwrap();
+	assert!(source.starts_with("#include <iostream>\n"));
+	assert!(source.contains("cin >> t;"));
+}
+
+#[tokio::test]
+async fn test_contest_submissions() {
+	let contest = SITE.contest("https://codeforces.com/contest/1294");
+	let submissions =
+		Codeforces.contest_submissions(&SITE.session("task_submissions.json"), &contest).await.unwrap();
+	let ids = submissions.iter().map(|submission| submission.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["69626171", "69625934", "69625512"]);
+}
+
+#[tokio::test]
+async fn test_invocation() {
+	let session = SITE.session("invocation.json");
+	let task = SITE.task("https://codeforces.com/contest/1294/problem/C");
+	let language = Language { id: "59".to_owned(), name: "Microsoft Visual C++ 2017".to_owned() };
+	let code = "#include <cstdio>\nint main() { long x = 1L << 31; printf(\"%ld\\n\", x); }\n";
+	let id = Codeforces.invocation_start(&session, &task, &language, code, "").await.unwrap();
+	assert_eq!(id, "284519723");
+	assert!(Codeforces.invocation_result(&session, &task, &id).await.unwrap().is_none());
+	let invocation = Codeforces.invocation_result(&session, &task, &id).await.unwrap().unwrap();
+	assert_eq!(invocation.output, "-2147483648\n");
+	assert_eq!(invocation.time, Some(std::time::Duration::from_millis(15)));
+	assert_eq!(invocation.memory, Some(3580 * 1024));
+}
+
+#[tokio::test]
+async fn test_contest_standings() {
+	let session = SITE.session("contest_standings.json");
+	let auth = Codeforces
+		.auth_deserialize(r#"{"jsessionid":{"cookie":"JSESSIONID=8F3A"},"username":"tester"}"#)
+		.unwrap();
+	Codeforces.auth_restore(&session, &auth).await.unwrap();
+	let contest = SITE.contest("https://codeforces.com/contest/1294");
+	let standings = Codeforces.contest_standings(&session, &contest).await.unwrap();
+	assert_eq!(standings.tasks, ["A", "B", "C"]);
+	assert_eq!(standings.rows.len(), 3);
+	assert_eq!(standings.rows[0].participant, "tourist");
+	assert_eq!(standings.rows[0].penalty, Some(std::time::Duration::from_secs(31 * 60)));
+	assert!(!standings.rows[0].own);
+	let own = &standings.rows[2];
+	assert_eq!((own.rank, own.participant.as_str(), own.points), (1873, "tester", 1.0));
+	assert!(own.own);
+	assert_eq!(own.results[1].as_ref().map(|result| result.rejected), Some(2));
+	assert!(own.results[2].is_none());
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+	let task = SITE.task("https://codeforces.com/problemset/problem/4/A");
+	match Codeforces.task_details(&SITE.session("rate_limit.json"), &task).await {
+		Err(Error::RateLimit { retry_after: None }) => (),
+		other => panic!("expected a rate limit error, got {:?}", other),
+	}
+}
@@ -0,0 +1,355 @@
+#![feature(never_type)]
+
+use async_trait::async_trait;
+use unijudge::{
+	debris::{self, Context, Document, Find}, http::{Client, Cookie}, reqwest::multipart, url::Url, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Submission, TaskDetails, Verdict
+};
+
+#[derive(Debug)]
+pub struct CSES;
+
+/// Languages accepted by the submission form, with the compiler option and file extension they
+/// need.
+const LANGUAGES: &[(&str, &str, &str, &str)] = &[
+	("C++11", "C++", "C++11", "cpp"),
+	("C++17", "C++", "C++17", "cpp"),
+	("Haskell", "Haskell", "", "hs"),
+	("Java", "Java", "", "java"),
+	("Node.js", "Node.js", "", "js"),
+	("Pascal", "Pascal", "", "pas"),
+	("Python 3 (CPython)", "Python3", "CPython", "py"),
+	("Python 3 (PyPy)", "Python3", "PyPy", "py"),
+	("Ruby", "Ruby", "", "rb"),
+	("Rust", "Rust", "", "rs"),
+	("Scala", "Scala", "", "scala"),
+];
+
+/// Only the statuses of the most recent submissions are checked, because each of them requires a
+/// separate request.
+const SUBMISSION_LIMIT: usize = 10;
+
+#[async_trait(?Send)]
+impl unijudge::Backend for CSES {
+	type CachedAuth = Cookie;
+	type Contest = !;
+	type Session = Client;
+	type Task = String;
+
+	fn accepted_domains(&self) -> &'static [&'static str] {
+		&["cses.fi"]
+	}
+
+	fn deconstruct_resource(
+		&self,
+		_domain: &str,
+		segments: &[&str],
+	) -> Result<Resource<Self::Contest, Self::Task>>
+	{
+		match segments {
+			["problemset", "task", task] | ["problemset", "view", task] => {
+				Ok(Resource::Task((*task).to_owned()))
+			},
+			_ => Err(Error::WrongTaskUrl),
+		}
+	}
+
+	fn connect(&self, client: Client, _domain: &str) -> Self::Session {
+		client
+	}
+
+	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
+		Ok(session.cookie_get("PHPSESSID")?)
+	}
+
+	fn auth_deserialize(&self, data: &str) -> Result<Self::CachedAuth> {
+		unijudge::deserialize_auth(data)
+	}
+
+	async fn auth_login(
+		&self,
+		session: &Self::Session,
+		username: &str,
+		password: &str,
+	) -> Result<()>
+	{
+		let csrf = csrf_token(&fetch(session, "https://cses.fi/login").await?)?;
+		let resp = session
+			.post("https://cses.fi/login".parse()?)
+			.form(&[("csrf_token", csrf.as_str()), ("nick", username), ("pass", password)])
+			.send()
+			.await?;
+		let url = resp.url().clone();
+		let doc = Document::new(&resp.text().await?);
+		if url.path() == "/login" {
+			Err(Error::WrongCredentials)
+		} else if is_logged_in(&doc) {
+			Ok(())
+		} else {
+			Err(Error::UnexpectedHTML(doc.error("unrecognized login outcome")))
+		}
+	}
+
+	async fn auth_restore(&self, session: &Self::Session, auth: &Self::CachedAuth) -> Result<()> {
+		session.cookie_set(auth.clone(), "https://cses.fi/")?;
+		Ok(())
+	}
+
+	fn auth_serialize(&self, auth: &Self::CachedAuth) -> Result<String> {
+		unijudge::serialize_auth(auth)
+	}
+
+	fn task_contest(&self, _: &Self::Task) -> Option<Self::Contest> {
+		None
+	}
+
+	async fn task_details(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<TaskDetails>
+	{
+		let url: Url = self.task_url(session, task)?.parse()?;
+		let resp = session.get(url.clone()).send().await?;
+		let doc = Document::new(&resp.text().await?);
+		let title = doc.find(".title-block > h1")?.text().string();
+		let mut time_limit = None;
+		let mut memory_limit = None;
+		for constraint in doc.find_all(".task-constraints > li") {
+			let constraint = constraint.text().string();
+			if constraint.starts_with("Time limit") {
+				time_limit = unijudge::parse_time_limit(&constraint);
+			} else if constraint.starts_with("Memory limit") {
+				memory_limit = unijudge::parse_memory_limit(&constraint);
+			}
+		}
+		// Examples are written as "Input:" and "Output:" paragraphs, each followed by a code block.
+		let blocks = doc.find_all(".md > pre").map(|pre| pre.text().string()).collect::<Vec<_>>();
+		if blocks.len() % 2 != 0 {
+			return Err(Error::UnexpectedHTML(doc.error("sample input with no matching output")));
+		}
+		let examples = blocks
+			.chunks(2)
+			.map(|pair| Example { input: end_line(&pair[0]), output: end_line(&pair[1]) })
+			.collect();
+		let mut statement = unijudge::statement::Rewrite::start(doc);
+		statement.fix_hide(|v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				["title-block", "task-constraints", "md"].iter().any(|class| {
+					v.has_class(class, unijudge::selectors::attr::CaseSensitivity::CaseSensitive)
+				})
+			} else {
+				false
+			}
+		});
+		statement.fix_override_csp();
+		statement.fix_traverse(|mut v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				unijudge::statement::fix_url(v, unijudge::qn!("href"), "/", "https://cses.fi");
+				unijudge::statement::fix_url(v, unijudge::qn!("src"), "/", "https://cses.fi");
+			}
+		});
+		Ok(TaskDetails {
+			id: task.clone(),
+			title,
+			contest_id: "problemset".to_owned(),
+			site_short: "cses".to_owned(),
+			examples: Some(examples),
+			statement: Some(statement.export()),
+			url: url.to_string(),
+			time_limit,
+			memory_limit,
+			input_file: None,
+			output_file: None,
+			interactive: false,
+		})
+	}
+
+	async fn task_languages(
+		&self,
+		_session: &Self::Session,
+		_task: &Self::Task,
+	) -> Result<Vec<Language>>
+	{
+		Ok(LANGUAGES
+			.iter()
+			.map(|(name, _, _, _)| Language { id: (*name).to_owned(), name: (*name).to_owned() })
+			.collect())
+	}
+
+	async fn task_submissions(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Submission>>
+	{
+		let doc = fetch(session, &self.task_url(session, task)?).await?;
+		if !is_logged_in(&doc) {
+			return Err(Error::AccessDenied);
+		}
+		let ids = doc
+			.find_all("a[href^=\"/problemset/result/\"]")
+			.map(|link| {
+				link.attr("href")?.map(|href| {
+					result_id(href).ok_or_else(|| format!("invalid submission url {:?}", href))
+				})
+			})
+			.take(SUBMISSION_LIMIT)
+			.collect::<debris::Result<Vec<_>>>()?;
+		let mut submissions = Vec::new();
+		for id in ids {
+			let doc = fetch(session, &format!("https://cses.fi/problemset/result/{}/", id)).await?;
+			submissions.push(Submission {
+				verdict: scrap_verdict(&doc)?,
+				id,
+				language: None,
+				time: None,
+			});
+		}
+		Ok(submissions)
+	}
+
+	async fn task_submit(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+	) -> Result<String>
+	{
+		let (_, lang, option, extension) =
+			LANGUAGES.iter().find(|(name, _, _, _)| *name == language.id).ok_or(Error::WrongData)?;
+		let doc = fetch(session, &format!("https://cses.fi/problemset/submit/{}/", task)).await?;
+		if !is_logged_in(&doc) {
+			return Err(Error::AccessDenied);
+		}
+		let csrf = csrf_token(&doc)?;
+		let file = multipart::Part::bytes(code.as_bytes().to_owned())
+			.file_name(format!("{}.{}", task, extension));
+		let resp = session
+			.post("https://cses.fi/course/send.php".parse()?)
+			.multipart(
+				multipart::Form::new()
+					.text("csrf_token", csrf)
+					.text("course", "problemset")
+					.text("task", task.clone())
+					.part("file", file)
+					.text("lang", *lang)
+					.text("option", *option)
+					.text("type", "course")
+					.text("target", "problemset"),
+			)
+			.send()
+			.await?;
+		// A successful submission redirects to its result page.
+		let url = resp.url().clone();
+		match result_id(url.path()) {
+			Some(id) => Ok(id),
+			None => Err(Error::UnexpectedHTML(
+				Document::new(&resp.text().await?).error("submission ID not found"),
+			)),
+		}
+	}
+
+	fn task_url(&self, _session: &Self::Session, task: &Self::Task) -> Result<String> {
+		Ok(format!("https://cses.fi/problemset/task/{}/", task))
+	}
+
+	fn submission_url(&self, _session: &Self::Session, _task: &Self::Task, id: &str) -> String {
+		format!("https://cses.fi/problemset/result/{}/", id)
+	}
+
+	fn contest_id(&self, contest: &Self::Contest) -> String {
+		*contest
+	}
+
+	fn contest_site_prefix(&self) -> &'static str {
+		"CSES"
+	}
+
+	async fn contest_tasks(
+		&self,
+		_session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Self::Task>>
+	{
+		*contest
+	}
+
+	fn contest_url(&self, contest: &Self::Contest) -> String {
+		*contest
+	}
+
+	async fn contest_title(
+		&self,
+		_session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<String>
+	{
+		*contest
+	}
+
+	async fn contests(
+		&self,
+		_session: &Self::Session,
+	) -> Result<Vec<ContestDetails<Self::Contest>>>
+	{
+		Ok(Vec::new())
+	}
+
+	fn name_short(&self) -> &'static str {
+		"cses"
+	}
+
+	fn supports_contests(&self) -> bool {
+		false
+	}
+}
+
+async fn fetch(session: &Client, url: &str) -> Result<Document> {
+	Ok(Document::new(&session.get(url.parse()?).send().await?.text().await?))
+}
+
+fn csrf_token(doc: &Document) -> Result<String> {
+	Ok(doc.find("input[name=\"csrf_token\"]")?.attr("value")?.string())
+}
+
+fn is_logged_in(doc: &Document) -> bool {
+	doc.find_all("a[href=\"/logout\"]").next().is_some()
+}
+
+fn result_id(path: &str) -> Option<String> {
+	let id = path.trim_start_matches("/problemset/result/").trim_end_matches('/');
+	if path.starts_with("/problemset/result/") && id.chars().all(|c| c.is_ascii_digit()) {
+		Some(id.to_owned())
+	} else {
+		None
+	}
+}
+
+fn scrap_verdict(doc: &Document) -> Result<Verdict> {
+	if doc.find("#status")?.text().string() != "READY" {
+		return Ok(Verdict::Pending { test: None });
+	}
+	let mut result = None;
+	for row in doc.find_all(".summary-table tr") {
+		if row.find_nth("td", 0)?.text().string() == "Result:" {
+			result = Some(row.find_nth("td", 1)?.text().string());
+		}
+	}
+	let cause = match result.as_ref().map(String::as_str) {
+		Some("ACCEPTED") => return Ok(Verdict::Accepted),
+		Some("WRONG ANSWER") => Some(RejectionCause::WrongAnswer),
+		Some("TIME LIMIT EXCEEDED") => Some(RejectionCause::TimeLimitExceeded),
+		Some("MEMORY LIMIT EXCEEDED") => Some(RejectionCause::MemoryLimitExceeded),
+		Some("RUNTIME ERROR") => Some(RejectionCause::RuntimeError),
+		Some("COMPILE ERROR") => Some(RejectionCause::CompilationError),
+		// Output limit exceeded does not have a matching cause.
+		Some("OUTPUT LIMIT EXCEEDED") => None,
+		_ => return Err(Error::UnexpectedHTML(doc.error("unrecognized submission result"))),
+	};
+	Ok(Verdict::Rejected { cause, test: None })
+}
+
+fn end_line(text: &str) -> String {
+	format!("{}\n", text.trim_end())
+}
@@ -0,0 +1,61 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Resource, Verdict};
+use unijudge_cses::CSES;
+
+const SITE: TestSite<CSES> =
+	TestSite { backend: &CSES, domain: "cses.fi", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+fn task(url: &str) -> String {
+	match SITE.resource(url) {
+		Resource::Task(task) => task,
+		Resource::Contest(contest) => contest,
+	}
+}
+
+#[test]
+fn test_deconstruct_url() {
+	assert_eq!(task("https://cses.fi/problemset/task/1068"), "1068");
+	assert_eq!(task("https://cses.fi/problemset/task/1068/"), "1068");
+	assert_eq!(task("https://cses.fi/problemset/view/1068/"), "1068");
+	assert!(CSES.deconstruct_url("https://cses.fi/problemset/list/").is_err());
+	assert!(CSES.deconstruct_url("https://example.com/problemset/task/1068").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = task("https://cses.fi/problemset/task/1068");
+	let details = CSES.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "1068");
+	assert_eq!(details.title, "Weird Algorithm");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(1)));
+	assert_eq!(details.memory_limit, Some(512 * 1024 * 1024));
+	assert!(!details.interactive);
+	let examples = details.examples.unwrap();
+	assert_eq!(examples.len(), 1);
+	assert_eq!(examples[0].input, "3\n");
+	assert_eq!(examples[0].output, "3 10 5 16 8 4 2 1\n");
+}
+
+#[tokio::test]
+async fn test_contests() {
+	// CSES does not list contests, so no requests should be made.
+	let contests = CSES.contests(&SITE.session("contests.json")).await.unwrap();
+	assert!(contests.is_empty());
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = SITE.session("task_submissions.json");
+	let auth = CSES.auth_deserialize(r#"{"cookie":"PHPSESSID=5f0e1a"}"#).unwrap();
+	CSES.auth_restore(&session, &auth).await.unwrap();
+	let submissions = CSES.task_submissions(&session, &"1068".to_owned()).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "1572031");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "1571988");
+	assert_eq!(submissions[1].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+	assert_eq!(submissions[2].id, "1571902");
+	assert_eq!(submissions[2].verdict, Verdict::Accepted);
+}
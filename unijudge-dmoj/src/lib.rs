@@ -0,0 +1,504 @@
+//! DMOJ is also run on many self-hosted instances, so domains other than dmoj.ca have to be
+//! configured with [`set_domains`] before interpreting URLs. Authentication uses [API tokens][1]
+//! instead of passwords, which also work for the submission form.
+//!
+//! [1]: https://dmoj.ca/edit/profile/
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{cell::Cell, sync::Mutex, time::Duration};
+use unijudge::{
+	chrono::{DateTime, Utc}, debris::Document, http::{Client, RequestBuilder}, json, reqwest::{header::AUTHORIZATION, StatusCode, Url}, scraper::Selector, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Submission, TaskDetails, Verdict
+};
+
+#[derive(Debug)]
+pub struct DMOJ;
+
+#[derive(Clone, Debug)]
+pub struct Contest {
+	domain: String,
+	key: String,
+}
+
+#[derive(Debug)]
+pub struct Task {
+	contest: Option<Contest>,
+	problem: String,
+}
+
+#[derive(Debug)]
+pub struct Session {
+	client: Client,
+	domain: String,
+	auth: Mutex<Option<CachedAuth>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedAuth {
+	username: String,
+	token: String,
+}
+
+const DEFAULT_DOMAINS: &[&str] = &["dmoj.ca"];
+
+thread_local! {
+	static DOMAINS: Cell<&'static [&'static str]> = Cell::new(DEFAULT_DOMAINS);
+}
+
+/// Sets the domains of self-hosted DMOJ instances, which are accepted in addition to dmoj.ca.
+pub fn set_domains<'a>(domains: impl IntoIterator<Item=&'a str>) {
+	let mut all = DEFAULT_DOMAINS.iter().map(|domain| (*domain).to_owned()).collect::<Vec<_>>();
+	for domain in domains {
+		if all.iter().all(|old| old != domain) {
+			all.push(domain.to_owned());
+		}
+	}
+	DOMAINS.with(|old| {
+		// The domain list has to be leaked to satisfy the Backend interface, so it is only
+		// recreated when the configuration actually changes.
+		if old.get().iter().ne(all.iter()) {
+			let all = all.into_iter().map(|domain| &*Box::leak(domain.into_boxed_str()));
+			old.set(Box::leak(all.collect::<Vec<_>>().into_boxed_slice()));
+		}
+	});
+}
+
+#[async_trait(?Send)]
+impl unijudge::Backend for DMOJ {
+	type CachedAuth = CachedAuth;
+	type Contest = Contest;
+	type Session = Session;
+	type Task = Task;
+
+	fn accepted_domains(&self) -> &'static [&'static str] {
+		DOMAINS.with(Cell::get)
+	}
+
+	fn deconstruct_resource(
+		&self,
+		domain: &str,
+		segments: &[&str],
+	) -> Result<Resource<Self::Contest, Self::Task>>
+	{
+		match segments {
+			["problem", problem] | ["problem", problem, "submit"] => {
+				Ok(Resource::Task(Task { contest: None, problem: (*problem).to_owned() }))
+			},
+			["contest", contest] | ["contest", contest, "ranking"] => {
+				Ok(Resource::Contest(Contest {
+					domain: domain.to_owned(),
+					key: (*contest).to_owned(),
+				}))
+			},
+			_ => Err(Error::WrongTaskUrl),
+		}
+	}
+
+	fn connect(&self, client: Client, domain: &str) -> Self::Session {
+		Session { client, domain: domain.to_owned(), auth: Mutex::new(None) }
+	}
+
+	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
+		Ok(session.auth.lock().map_err(|_| Error::StateCorruption)?.clone())
+	}
+
+	fn auth_deserialize(&self, data: &str) -> Result<Self::CachedAuth> {
+		unijudge::deserialize_auth(data)
+	}
+
+	/// Logs in with an API token passed as the password.
+	async fn auth_login(
+		&self,
+		session: &Self::Session,
+		username: &str,
+		password: &str,
+	) -> Result<()>
+	{
+		let url = session.api(&format!("user/{}", username))?;
+		let resp = session.client.get(url).header(AUTHORIZATION, bearer(password)).send().await?;
+		match resp.status() {
+			StatusCode::OK => {
+				*session.auth.lock().map_err(|_| Error::StateCorruption)? =
+					Some(CachedAuth { username: username.to_owned(), token: password.to_owned() });
+				Ok(())
+			},
+			StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND => Err(Error::WrongCredentials),
+			_ => Err(Error::UnexpectedResponse {
+				endpoint: "/api/v2/user/{}",
+				message: "unrecognized login outcome",
+				resp_raw: resp.text().await?,
+				inner: None,
+			}),
+		}
+	}
+
+	async fn auth_restore(&self, session: &Self::Session, auth: &Self::CachedAuth) -> Result<()> {
+		*session.auth.lock().map_err(|_| Error::StateCorruption)? = Some(auth.clone());
+		Ok(())
+	}
+
+	fn auth_serialize(&self, auth: &Self::CachedAuth) -> Result<String> {
+		unijudge::serialize_auth(auth)
+	}
+
+	fn task_contest(&self, task: &Self::Task) -> Option<Self::Contest> {
+		task.contest.clone()
+	}
+
+	async fn task_details(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<TaskDetails>
+	{
+		let problem = session.problem(&task.problem).await?;
+		// The API does not include the statement, so it has to be taken from the problem page.
+		let url = self.task_url(session, task)?;
+		let resp = session.authorize(session.client.get(url.parse()?))?.send().await?;
+		let doc = Document::new(&resp.text().await?);
+		let examples = find_examples(&doc);
+		let site = format!("https://{}", session.domain);
+		let mut statement = unijudge::statement::Rewrite::start(doc);
+		statement.fix_hide(|v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				["problem-title", "content-description"].iter().any(|class| {
+					v.has_class(class, unijudge::selectors::attr::CaseSensitivity::CaseSensitive)
+				})
+			} else {
+				false
+			}
+		});
+		statement.fix_override_csp();
+		statement.fix_traverse(|mut v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				unijudge::statement::fix_url(v, unijudge::qn!("href"), "//", "https:");
+				unijudge::statement::fix_url(v, unijudge::qn!("src"), "//", "https:");
+				unijudge::statement::fix_url(v, unijudge::qn!("href"), "/", &site);
+				unijudge::statement::fix_url(v, unijudge::qn!("src"), "/", &site);
+			}
+		});
+		Ok(TaskDetails {
+			id: problem.code,
+			title: problem.name,
+			contest_id: task.contest.as_ref().map_or("problems".to_owned(), |c| c.key.clone()),
+			site_short: "dmoj".to_owned(),
+			examples,
+			statement: Some(statement.export()),
+			url,
+			time_limit: Some(Duration::from_secs_f64(problem.time_limit)),
+			memory_limit: Some(problem.memory_limit * 1024),
+			input_file: None,
+			output_file: None,
+			interactive: false,
+		})
+	}
+
+	async fn task_languages(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Language>>
+	{
+		let problem = session.problem(&task.problem).await?;
+		let languages =
+			session.api_get::<api::Objects<api::Language>>("languages", "/languages").await?;
+		Ok(languages
+			.objects
+			.into_iter()
+			.filter(|language| problem.languages.contains(&language.key))
+			.map(|language| Language {
+				id: language.id.to_string(),
+				name: language.short_name.unwrap_or(language.key),
+			})
+			.collect())
+	}
+
+	async fn task_submissions(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Submission>>
+	{
+		let username = session.username()?;
+		let path = format!("submissions?user={}&problem={}", username, task.problem);
+		let mut submissions =
+			session.api_get::<api::Objects<api::Submission>>(&path, "/submissions").await?.objects;
+		submissions.sort_by_key(|submission| std::cmp::Reverse(submission.id));
+		Ok(submissions
+			.into_iter()
+			.map(|submission| Submission {
+				id: submission.id.to_string(),
+				verdict: submission.verdict(),
+				language: None,
+				time: None,
+			})
+			.collect())
+	}
+
+	async fn task_submit(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+	) -> Result<String>
+	{
+		session.username()?;
+		let url: Url = format!("{}/submit", self.task_url(session, task)?).parse()?;
+		let resp = session
+			.authorize(session.client.post(url))?
+			.form(&[("source", code), ("language", language.id.as_str())])
+			.send()
+			.await?;
+		// A successful submission redirects to the submission page, and a failed one shows the form
+		// again with error messages.
+		let url = resp.url().clone();
+		match url.path_segments().map(|segments| segments.collect::<Vec<_>>()).as_deref() {
+			Some(["submission", id]) | Some(["submission", id, ""]) => Ok((*id).to_owned()),
+			_ => Err(Error::UnexpectedResponse {
+				endpoint: "/problem/{}/submit",
+				message: "submission was not accepted",
+				resp_raw: resp.text().await?,
+				inner: None,
+			}),
+		}
+	}
+
+	fn task_url(&self, session: &Self::Session, task: &Self::Task) -> Result<String> {
+		Ok(format!("https://{}/problem/{}", session.domain, task.problem))
+	}
+
+	fn submission_url(&self, session: &Self::Session, _task: &Self::Task, id: &str) -> String {
+		format!("https://{}/submission/{}", session.domain, id)
+	}
+
+	fn contest_id(&self, contest: &Self::Contest) -> String {
+		contest.key.clone()
+	}
+
+	fn contest_site_prefix(&self) -> &'static str {
+		"DMOJ"
+	}
+
+	async fn contest_tasks(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Self::Task>>
+	{
+		let path = format!("contest/{}", contest.key);
+		let details = session.api_get::<api::Object<api::Contest>>(&path, "/contest/{}").await?;
+		// Problems are hidden from the API until the contest starts.
+		if details.object.problems.is_empty() {
+			return Err(Error::NotYetStarted);
+		}
+		Ok(details
+			.object
+			.problems
+			.into_iter()
+			.map(|problem| Task { contest: Some(contest.clone()), problem: problem.code })
+			.collect())
+	}
+
+	fn contest_url(&self, contest: &Self::Contest) -> String {
+		format!("https://{}/contest/{}", contest.domain, contest.key)
+	}
+
+	async fn contest_title(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<String>
+	{
+		let path = format!("contest/{}", contest.key);
+		Ok(session.api_get::<api::Object<api::Contest>>(&path, "/contest/{}").await?.object.name)
+	}
+
+	async fn contests(
+		&self,
+		session: &Self::Session,
+	) -> Result<Vec<ContestDetails<Self::Contest>>>
+	{
+		let contests =
+			session.api_get::<api::Objects<api::ContestSummary>>("contests", "/contests").await?;
+		let now = Utc::now();
+		let mut details = Vec::new();
+		for contest in contests.objects {
+			let start = parse_time(&contest.start_time)?;
+			// The API lists all past contests too, and only upcoming or running ones are useful.
+			if parse_time(&contest.end_time)? < now {
+				continue;
+			}
+			let id = Contest { domain: session.domain.clone(), key: contest.key };
+			details.push(ContestDetails { id, title: contest.name, start: Some(start) });
+		}
+		Ok(details)
+	}
+
+	fn name_short(&self) -> &'static str {
+		"dmoj"
+	}
+
+	fn supports_contests(&self) -> bool {
+		true
+	}
+}
+
+impl Session {
+	fn api(&self, path: &str) -> Result<Url> {
+		Ok(format!("https://{}/api/v2/{}", self.domain, path).parse()?)
+	}
+
+	async fn api_get<T: DeserializeOwned>(&self, path: &str, endpoint: &'static str) -> Result<T> {
+		let resp = self.authorize(self.client.get(self.api(path)?))?.send().await?;
+		match resp.status() {
+			StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::AccessDenied),
+			StatusCode::NOT_FOUND => Err(Error::WrongData),
+			_ => Ok(json::from_resp::<api::Response<T>>(resp, endpoint).await?.data),
+		}
+	}
+
+	fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+		Ok(match &*self.auth.lock().map_err(|_| Error::StateCorruption)? {
+			Some(auth) => request.header(AUTHORIZATION, bearer(&auth.token)),
+			None => request,
+		})
+	}
+
+	async fn problem(&self, code: &str) -> Result<api::Problem> {
+		let path = format!("problem/{}", code);
+		Ok(self.api_get::<api::Object<api::Problem>>(&path, "/problem/{}").await?.object)
+	}
+
+	fn username(&self) -> Result<String> {
+		let auth = self.auth.lock().map_err(|_| Error::StateCorruption)?;
+		Ok(auth.as_ref().ok_or(Error::AccessDenied)?.username.clone())
+	}
+}
+
+fn bearer(token: &str) -> String {
+	format!("Bearer {}", token)
+}
+
+fn parse_time(time: &str) -> Result<DateTime<unijudge::chrono::FixedOffset>> {
+	DateTime::parse_from_rfc3339(time).map_err(|e| Error::UnexpectedJSON {
+		endpoint: "/contests",
+		resp_raw: time.to_owned(),
+		inner: Some(Box::new(e)),
+	})
+}
+
+mod api {
+	use super::*;
+
+	#[derive(Debug, Deserialize)]
+	pub struct Response<T> {
+		pub data: T,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Object<T> {
+		pub object: T,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Objects<T> {
+		pub objects: Vec<T>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Problem {
+		pub code: String,
+		pub name: String,
+		/// Time limit in seconds.
+		pub time_limit: f64,
+		/// Memory limit in kilobytes.
+		pub memory_limit: u64,
+		pub languages: Vec<String>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct ContestSummary {
+		pub key: String,
+		pub name: String,
+		pub start_time: String,
+		pub end_time: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Contest {
+		pub name: String,
+		#[serde(default)]
+		pub problems: Vec<ContestProblem>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct ContestProblem {
+		pub code: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Language {
+		pub id: u64,
+		pub key: String,
+		pub short_name: Option<String>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Submission {
+		pub id: u64,
+		pub status: String,
+		pub result: Option<String>,
+	}
+
+	impl Submission {
+		pub fn verdict(&self) -> Verdict {
+			if self.status != "D" && self.status != "IE" && self.status != "CE" {
+				return Verdict::Pending { test: None };
+			}
+			let cause = match self.result.as_ref().map(String::as_str) {
+				Some("AC") => return Verdict::Accepted,
+				Some("AB") => return Verdict::Skipped,
+				Some("WA") => Some(RejectionCause::WrongAnswer),
+				Some("TLE") => Some(RejectionCause::TimeLimitExceeded),
+				Some("MLE") => Some(RejectionCause::MemoryLimitExceeded),
+				Some("IR") | Some("RTE") => Some(RejectionCause::RuntimeError),
+				Some("CE") => Some(RejectionCause::CompilationError),
+				Some("IE") => Some(RejectionCause::SystemError),
+				// Output limit exceeded and short circuiting do not have a matching cause.
+				_ => None,
+			};
+			Verdict::Rejected { cause, test: None }
+		}
+	}
+}
+
+/// Finds the samples by the headers above their code blocks, like "Sample Input 1" and "Sample
+/// Output 1", so that other code blocks in the statement are not mistaken for samples.
+fn find_examples(doc: &Document) -> Option<Vec<Example>> {
+	let selector = Selector::parse(
+		".content-description h1, .content-description h2, .content-description h3, \
+		 .content-description h4, .content-description h5, .content-description h6, \
+		 .content-description pre",
+	)
+	.unwrap();
+	let mut inputs = Vec::new();
+	let mut outputs = Vec::new();
+	let mut header = String::new();
+	for element in doc.tree.select(&selector) {
+		let text = element.text().collect::<String>();
+		if element.value().name() != "pre" {
+			header = text.trim().to_lowercase();
+		} else if header.starts_with("sample input") {
+			inputs.push(text);
+			header.clear();
+		} else if header.starts_with("sample output") {
+			outputs.push(text);
+			header.clear();
+		}
+	}
+	if inputs.is_empty() || inputs.len() != outputs.len() {
+		return None;
+	}
+	Some(inputs.into_iter().zip(outputs).map(|(input, output)| Example { input, output }).collect())
+}
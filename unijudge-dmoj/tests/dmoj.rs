@@ -0,0 +1,74 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Verdict};
+use unijudge_dmoj::DMOJ;
+
+const SITE: TestSite<DMOJ> =
+	TestSite { backend: &DMOJ, domain: "dmoj.ca", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	let url = "https://dmoj.ca/problem/aplusb";
+	assert_eq!(DMOJ.task_url(&session, &SITE.task(url)).unwrap(), url);
+	let contest_url = DMOJ.contest_url(&SITE.contest("https://dmoj.ca/contest/dmpg/ranking/"));
+	assert_eq!(contest_url, "https://dmoj.ca/contest/dmpg");
+	assert!(DMOJ.deconstruct_url("https://dmoj.ca/user/tester").is_err());
+	assert!(DMOJ.deconstruct_url("https://judge.example.com/problem/aplusb").unwrap().is_none());
+	unijudge_dmoj::set_domains(vec!["judge.example.com"]);
+	let contest = SITE.contest("https://judge.example.com/contest/practice");
+	assert_eq!(DMOJ.contest_url(&contest), "https://judge.example.com/contest/practice");
+	assert!(DMOJ.deconstruct_url("https://dmoj.ca/problem/aplusb").unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = SITE.task("https://dmoj.ca/problem/aplusb");
+	let details = DMOJ.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "aplusb");
+	assert_eq!(details.title, "A Plus B");
+	assert_eq!(details.contest_id, "problems");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(2)));
+	assert_eq!(details.memory_limit, Some(64 * 1024 * 1024));
+	let examples = details.examples.unwrap();
+	assert_eq!(examples.len(), 1);
+	assert_eq!(examples[0].input, "2\n1 1\n-1 0\n");
+	assert_eq!(examples[0].output, "2\n-1\n");
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = DMOJ.contests(&SITE.session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 2);
+	assert_eq!(DMOJ.contest_id(&contests[0].id), "ccc21j");
+	assert_eq!(contests[0].title, "CCC '21 Junior");
+	assert_eq!(contests[0].start.unwrap().to_rfc3339(), "2099-02-17T17:00:00+00:00");
+	assert_eq!(DMOJ.contest_id(&contests[1].id), "dmpg");
+}
+
+#[tokio::test]
+async fn test_contest_tasks() {
+	let session = SITE.session("contest_tasks.json");
+	let contest = SITE.contest("https://dmoj.ca/contest/dmpg");
+	let tasks = DMOJ.contest_tasks(&session, &contest).await.unwrap();
+	let urls = tasks.iter().map(|task| DMOJ.task_url(&session, task).unwrap()).collect::<Vec<_>>();
+	assert_eq!(urls, vec!["https://dmoj.ca/problem/aplusb", "https://dmoj.ca/problem/dmpg17b1"]);
+	assert_eq!(DMOJ.contest_id(&DMOJ.task_contest(&tasks[0]).unwrap()), "dmpg");
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = SITE.session("task_submissions.json");
+	let auth = DMOJ.auth_deserialize(r#"{"username":"tester","token":"AAAA"}"#).unwrap();
+	DMOJ.auth_restore(&session, &auth).await.unwrap();
+	let task = SITE.task("https://dmoj.ca/problem/aplusb");
+	let submissions = DMOJ.task_submissions(&session, &task).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "1950412");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "1950377");
+	assert_eq!(submissions[1].verdict, Verdict::Accepted);
+	assert_eq!(submissions[2].id, "1950101");
+	assert_eq!(submissions[2].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+}
@@ -0,0 +1,588 @@
+//! DOMjudge is self-hosted, so the servers it connects to have to be configured with [`set_sites`]
+//! before interpreting URLs. Everything is done through the [REST API][1], with contests
+//! identified by their API URLs, like `https://judge.example.com/domjudge/api/v4/contests/3`.
+//! Tasks and submissions link to the team interface instead, which does not have the contest in
+//! its paths, so task URLs carry it in the query string, like
+//! `https://judge.example.com/domjudge/team/problems/sum?contest=3`.
+//!
+//! [1]: https://www.domjudge.org/docs/manual/main/develop.html#api
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+	cell::{Cell, RefCell}, collections::BTreeMap, io::{Cursor, Read}, sync::Mutex, time::Duration
+};
+use unijudge::{
+	chrono::DateTime, http::{Client, RequestBuilder, Response}, json, reqwest::{multipart, StatusCode, Url}, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Statement, Submission, TaskDetails, Verdict, URL
+};
+
+#[derive(Debug)]
+pub struct DOMjudge;
+
+#[derive(Clone, Debug)]
+pub struct Contest {
+	site: String,
+	id: String,
+}
+
+#[derive(Debug)]
+pub struct Task {
+	contest: Contest,
+	problem: String,
+}
+
+#[derive(Debug)]
+pub struct Session {
+	client: Client,
+	site: String,
+	auth: Mutex<Option<CachedAuth>>,
+}
+
+/// The API uses HTTP basic authentication, so the credentials themselves have to be kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedAuth {
+	username: String,
+	password: String,
+}
+
+#[derive(Debug)]
+struct Site {
+	url: String,
+	domain: String,
+	prefix: Vec<String>,
+}
+
+thread_local! {
+	static SITES: RefCell<Vec<Site>> = RefCell::new(Vec::new());
+	static DOMAINS: Cell<&'static [&'static str]> = Cell::new(&[]);
+}
+
+/// Sets the base URLs of DOMjudge servers, like `https://judge.example.com/domjudge`.
+pub fn set_sites<'a>(urls: impl IntoIterator<Item=&'a str>) -> Result<()> {
+	let mut sites = Vec::new();
+	for url in urls {
+		let url = url.trim_end_matches('/');
+		let parsed: Url = url.parse()?;
+		let domain = parsed.domain().ok_or(Error::WrongData)?.to_owned();
+		let prefix = parsed.path_segments().into_iter().flatten().filter(|s| !s.is_empty());
+		let prefix = prefix.map(str::to_owned).collect();
+		sites.push(Site { url: url.to_owned(), domain, prefix });
+	}
+	SITES.with(|old| {
+		let mut old = old.borrow_mut();
+		// The domain list has to be leaked to satisfy the Backend interface, so it is only
+		// recreated when the configuration actually changes.
+		if old.iter().map(|site| &site.url).ne(sites.iter().map(|site| &site.url)) {
+			let mut domains = Vec::new();
+			for site in &sites {
+				if domains.iter().all(|domain| *domain != site.domain) {
+					domains.push(&*Box::leak(site.domain.clone().into_boxed_str()));
+				}
+			}
+			DOMAINS.with(|old| old.set(Box::leak(domains.into_boxed_slice())));
+			*old = sites;
+		}
+	});
+	Ok(())
+}
+
+#[async_trait(?Send)]
+impl unijudge::Backend for DOMjudge {
+	type CachedAuth = CachedAuth;
+	type Contest = Contest;
+	type Session = Session;
+	type Task = Task;
+
+	fn accepted_domains(&self) -> &'static [&'static str] {
+		DOMAINS.with(Cell::get)
+	}
+
+	fn deconstruct_url(&self, url: &str) -> Result<Option<URL<Self::Contest, Self::Task>>> {
+		let url: Url = url.parse()?;
+		let domain = url.domain().ok_or(Error::WrongTaskUrl)?;
+		if !self.accepted_domains().contains(&domain) {
+			return Ok(None);
+		}
+		let segments = url
+			.path_segments()
+			.ok_or(Error::WrongTaskUrl)?
+			.filter(|s| !s.is_empty())
+			.collect::<Vec<_>>();
+		let contest = url.query_pairs().find(|(key, _)| key == "contest").map(|(_, id)| id);
+		let resource = deconstruct(domain, &segments, contest.as_deref())?;
+		Ok(Some(URL { domain: domain.to_owned(), site: format!("https://{}", domain), resource }))
+	}
+
+	fn deconstruct_resource(
+		&self,
+		domain: &str,
+		segments: &[&str],
+	) -> Result<Resource<Self::Contest, Self::Task>>
+	{
+		deconstruct(domain, segments, None)
+	}
+
+	fn connect(&self, client: Client, domain: &str) -> Self::Session {
+		let site = SITES.with(|sites| {
+			sites.borrow().iter().find(|site| site.domain == domain).map(|site| site.url.clone())
+		});
+		let site = site.unwrap_or_else(|| format!("https://{}", domain));
+		Session { client, site, auth: Mutex::new(None) }
+	}
+
+	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
+		Ok(session.auth.lock().map_err(|_| Error::StateCorruption)?.clone())
+	}
+
+	fn auth_deserialize(&self, data: &str) -> Result<Self::CachedAuth> {
+		unijudge::deserialize_auth(data)
+	}
+
+	async fn auth_login(
+		&self,
+		session: &Self::Session,
+		username: &str,
+		password: &str,
+	) -> Result<()>
+	{
+		let auth = CachedAuth { username: username.to_owned(), password: password.to_owned() };
+		let resp = session.client.get(session.api("user")?).basic_auth(username, password);
+		match resp.send().await?.status() {
+			StatusCode::OK => {
+				*session.auth.lock().map_err(|_| Error::StateCorruption)? = Some(auth);
+				Ok(())
+			},
+			StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::WrongCredentials),
+			status => Err(Error::UnexpectedResponse {
+				endpoint: "/user",
+				message: "unrecognized login outcome",
+				resp_raw: status.to_string(),
+				inner: None,
+			}),
+		}
+	}
+
+	async fn auth_restore(&self, session: &Self::Session, auth: &Self::CachedAuth) -> Result<()> {
+		*session.auth.lock().map_err(|_| Error::StateCorruption)? = Some(auth.clone());
+		Ok(())
+	}
+
+	fn auth_serialize(&self, auth: &Self::CachedAuth) -> Result<String> {
+		unijudge::serialize_auth(auth)
+	}
+
+	fn task_contest(&self, task: &Self::Task) -> Option<Self::Contest> {
+		Some(task.contest.clone())
+	}
+
+	async fn task_details(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<TaskDetails>
+	{
+		let path = format!("contests/{}/problems/{}", task.contest.id, task.problem);
+		let resp = check_status(session.get(&path)?.send().await?)?;
+		let problem = json::from_resp::<api::Problem>(resp, "/contests/{}/problems/{}").await?;
+		// Samples are only available as a zip archive, in the same format as in the team interface.
+		let resp = session.get(&format!("{}/samples.zip", path))?.send().await?;
+		let examples = match resp.status() {
+			StatusCode::NOT_FOUND => None,
+			_ => Some(parse_samples(&check_status(resp)?.bytes().await?)?),
+		};
+		let resp = session.get(&format!("{}/statement", path))?.send().await?;
+		let statement = match resp.status() {
+			StatusCode::OK => Some(Statement::PDF { pdf: resp.bytes().await? }),
+			_ => None,
+		};
+		Ok(TaskDetails {
+			id: problem.label,
+			title: problem.name,
+			contest_id: task.contest.id.clone(),
+			site_short: "domjudge".to_owned(),
+			examples,
+			statement,
+			url: self.task_url(session, task)?,
+			time_limit: problem.time_limit.map(Duration::from_secs_f64),
+			memory_limit: None,
+			input_file: None,
+			output_file: None,
+			interactive: false,
+		})
+	}
+
+	async fn task_languages(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Language>>
+	{
+		Ok(fetch_languages(session, &task.contest)
+			.await?
+			.into_iter()
+			.map(|language| Language { id: language.id.into_string(), name: language.name })
+			.collect())
+	}
+
+	async fn task_submissions(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Submission>>
+	{
+		// Team accounts only see their own submissions and judgements.
+		let path = format!("contests/{}/submissions", task.contest.id);
+		let resp = check_status(session.get(&path)?.send().await?)?;
+		let submissions =
+			json::from_resp::<Vec<api::Submission>>(resp, "/contests/{}/submissions").await?;
+		let path = format!("contests/{}/judgements", task.contest.id);
+		let resp = check_status(session.get(&path)?.send().await?)?;
+		let judgements =
+			json::from_resp::<Vec<api::Judgement>>(resp, "/contests/{}/judgements").await?;
+		Ok(submissions
+			.into_iter()
+			.rev()
+			.filter(|submission| submission.problem_id.as_str() == task.problem)
+			.map(|submission| {
+				let id = submission.id.into_string();
+				let judgement = judgements
+					.iter()
+					.filter(|judgement| judgement.valid && judgement.submission_id.as_str() == id)
+					.last();
+				let verdict = judgement.map_or(Verdict::Pending { test: None }, |judgement| {
+					to_verdict(judgement.judgement_type_id.as_ref().map(String::as_str))
+				});
+				Submission { id, verdict, language: None, time: None }
+			})
+			.collect())
+	}
+
+	async fn task_submit(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+	) -> Result<String>
+	{
+		let languages = fetch_languages(session, &task.contest).await?;
+		let extension = languages
+			.iter()
+			.find(|lang| lang.id.as_str() == language.id)
+			.and_then(|lang| lang.extensions.first())
+			.ok_or(Error::WrongData)?;
+		let file = multipart::Part::bytes(code.as_bytes().to_owned())
+			.file_name(format!("{}.{}", task.problem, extension));
+		let form = multipart::Form::new()
+			.text("problem", task.problem.clone())
+			.text("language", language.id.clone())
+			.part("code[]", file);
+		let path = format!("contests/{}/submissions", task.contest.id);
+		let resp = session.post(&path)?.multipart(form).send().await?;
+		let resp = check_status(resp)?;
+		let submission = json::from_resp::<api::Created>(resp, "/contests/{}/submissions").await?;
+		Ok(submission.id.into_string())
+	}
+
+	fn task_url(&self, _session: &Self::Session, task: &Self::Task) -> Result<String> {
+		let Task { contest, problem } = task;
+		Ok(format!("{}/team/problems/{}?contest={}", contest.site, problem, contest.id))
+	}
+
+	fn submission_url(&self, _session: &Self::Session, task: &Self::Task, id: &str) -> String {
+		format!("{}/team/submission/{}", task.contest.site, id)
+	}
+
+	fn contest_id(&self, contest: &Self::Contest) -> String {
+		contest.id.clone()
+	}
+
+	fn contest_site_prefix(&self) -> &'static str {
+		"DOMjudge"
+	}
+
+	async fn contest_tasks(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Self::Task>>
+	{
+		let path = format!("contests/{}/problems", contest.id);
+		let resp = check_status(session.get(&path)?.send().await?)?;
+		let mut problems =
+			json::from_resp::<Vec<api::Problem>>(resp, "/contests/{}/problems").await?;
+		// Problems are hidden from teams until the contest starts.
+		if problems.is_empty() {
+			return Err(Error::NotYetStarted);
+		}
+		problems.sort_by_key(|problem| problem.ordinal);
+		Ok(problems
+			.into_iter()
+			.map(|problem| Task { contest: contest.clone(), problem: problem.id.into_string() })
+			.collect())
+	}
+
+	fn contest_url(&self, contest: &Self::Contest) -> String {
+		format!("{}/api/v4/contests/{}", contest.site, contest.id)
+	}
+
+	async fn contest_title(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<String>
+	{
+		let resp = check_status(session.get(&format!("contests/{}", contest.id))?.send().await?)?;
+		Ok(json::from_resp::<api::Contest>(resp, "/contests/{}").await?.title())
+	}
+
+	async fn contests(
+		&self,
+		session: &Self::Session,
+	) -> Result<Vec<ContestDetails<Self::Contest>>>
+	{
+		let resp = check_status(session.get("contests")?.send().await?)?;
+		let contests = json::from_resp::<Vec<api::Contest>>(resp, "/contests").await?;
+		let mut details = Vec::new();
+		for contest in contests {
+			// Contests without a start time are not scheduled yet, so there is nothing to wait for.
+			let start = match &contest.start_time {
+				Some(start) => start,
+				None => continue,
+			};
+			let start =
+				DateTime::parse_from_rfc3339(start).map_err(|e| Error::UnexpectedJSON {
+					endpoint: "/contests",
+					resp_raw: start.clone(),
+					inner: Some(Box::new(e)),
+				})?;
+			let title = contest.title();
+			let id = Contest { site: session.site.clone(), id: contest.id.into_string() };
+			details.push(ContestDetails { id, title, start: Some(start) });
+		}
+		Ok(details)
+	}
+
+	fn name_short(&self) -> &'static str {
+		"domjudge"
+	}
+
+	fn supports_contests(&self) -> bool {
+		true
+	}
+}
+
+impl Session {
+	fn api(&self, path: &str) -> Result<Url> {
+		Ok(format!("{}/api/v4/{}", self.site, path).parse()?)
+	}
+
+	fn get(&self, path: &str) -> Result<RequestBuilder> {
+		self.authorize(self.client.get(self.api(path)?))
+	}
+
+	fn post(&self, path: &str) -> Result<RequestBuilder> {
+		self.authorize(self.client.post(self.api(path)?))
+	}
+
+	fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+		Ok(match &*self.auth.lock().map_err(|_| Error::StateCorruption)? {
+			Some(auth) => request.basic_auth(&auth.username, &auth.password),
+			None => request,
+		})
+	}
+}
+
+async fn fetch_languages(session: &Session, contest: &Contest) -> Result<Vec<api::Language>> {
+	let path = format!("contests/{}/languages", contest.id);
+	let resp = check_status(session.get(&path)?.send().await?)?;
+	json::from_resp(resp, "/contests/{}/languages").await
+}
+
+/// Interprets a path on one of the configured sites. Team interface task URLs are only valid if the
+/// contest was passed in the query string.
+fn deconstruct(
+	domain: &str,
+	segments: &[&str],
+	contest: Option<&str>,
+) -> Result<Resource<Contest, Task>>
+{
+	let (site, segments) = SITES
+		.with(|sites| {
+			sites.borrow().iter().find_map(|site| {
+				let matches = site.domain == domain
+					&& site.prefix.len() <= segments.len()
+					&& site.prefix.iter().zip(segments).all(|(a, b)| a == b);
+				if matches {
+					Some((site.url.clone(), &segments[site.prefix.len()..]))
+				} else {
+					None
+				}
+			})
+		})
+		.ok_or(Error::WrongTaskUrl)?;
+	let segments = match (segments, contest) {
+		(["team", "problems", problem], Some(contest)) => {
+			return Ok(Resource::Task(Task {
+				contest: Contest { site, id: contest.to_owned() },
+				problem: (*problem).to_owned(),
+			}));
+		},
+		(["api", "v4", ..], _) => &segments[2..],
+		(["api", ..], _) => &segments[1..],
+		_ => return Err(Error::WrongTaskUrl),
+	};
+	match segments {
+		["contests", contest] => Ok(Resource::Contest(Contest { site, id: (*contest).to_owned() })),
+		["contests", contest, "problems", problem] => Ok(Resource::Task(Task {
+			contest: Contest { site, id: (*contest).to_owned() },
+			problem: (*problem).to_owned(),
+		})),
+		_ => Err(Error::WrongTaskUrl),
+	}
+}
+
+fn check_status(resp: Response) -> Result<Response> {
+	match resp.status() {
+		StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::AccessDenied),
+		StatusCode::NOT_FOUND => Err(Error::WrongData),
+		_ => Ok(resp),
+	}
+}
+
+/// Pairs the `.in` files in the sample archive with the `.ans` or `.out` files of the same name.
+fn parse_samples(archive: &[u8]) -> Result<Vec<Example>> {
+	let invalid = |e: zip::result::ZipError| Error::UnexpectedResponse {
+		endpoint: "/contests/{}/problems/{}/samples.zip",
+		message: "invalid sample archive",
+		resp_raw: String::new(),
+		inner: Some(Box::new(e)),
+	};
+	let mut archive = zip::ZipArchive::new(Cursor::new(archive)).map_err(invalid)?;
+	let mut inputs = BTreeMap::new();
+	let mut outputs = BTreeMap::new();
+	for i in 0..archive.len() {
+		let mut file = archive.by_index(i).map_err(invalid)?;
+		let name = file.name().to_owned();
+		let mut content = String::new();
+		file.read_to_string(&mut content).map_err(|e| invalid(e.into()))?;
+		if name.ends_with(".in") {
+			inputs.insert(name.trim_end_matches(".in").to_owned(), content);
+		} else if name.ends_with(".ans") || name.ends_with(".out") {
+			let name = name.trim_end_matches(".ans").trim_end_matches(".out");
+			outputs.insert(name.to_owned(), content);
+		}
+	}
+	let mut examples = inputs
+		.into_iter()
+		.filter_map(|(name, input)| Some((name.clone(), input, outputs.remove(&name)?)))
+		.collect::<Vec<_>>();
+	// Samples are usually numbered, so sort them numerically rather than lexicographically.
+	examples.sort_by_key(|(name, _, _)| (name.len(), name.clone()));
+	Ok(examples.into_iter().map(|(_, input, output)| Example { input, output }).collect())
+}
+
+/// Maps the judgement type identifiers from the [CCS specification][1].
+///
+/// [1]: https://ccs-specs.icpc.io/contest_api#judgement-types
+fn to_verdict(judgement_type: Option<&str>) -> Verdict {
+	let cause = match judgement_type {
+		None => return Verdict::Pending { test: None },
+		Some("AC") => return Verdict::Accepted,
+		Some("WA") | Some("PE") | Some("NO") => Some(RejectionCause::WrongAnswer),
+		Some("TLE") => Some(RejectionCause::TimeLimitExceeded),
+		Some("RTE") => Some(RejectionCause::RuntimeError),
+		Some("MLE") => Some(RejectionCause::MemoryLimitExceeded),
+		Some("CE") => Some(RejectionCause::CompilationError),
+		Some("JE") => Some(RejectionCause::SystemError),
+		// Output limit exceeded and custom judgement types do not have a matching cause.
+		Some(_) => None,
+	};
+	Verdict::Rejected { cause, test: None }
+}
+
+mod api {
+	use serde::Deserialize;
+
+	/// Identifiers are strings since API version 4, but older servers still return numbers.
+	#[derive(Debug, Deserialize)]
+	#[serde(untagged)]
+	pub enum Id {
+		String(String),
+		Number(u64),
+	}
+
+	impl Id {
+		pub fn as_str(&self) -> std::borrow::Cow<str> {
+			match self {
+				Id::String(id) => id.as_str().into(),
+				Id::Number(id) => id.to_string().into(),
+			}
+		}
+
+		pub fn into_string(self) -> String {
+			self.as_str().into_owned()
+		}
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Contest {
+		pub id: Id,
+		pub name: String,
+		#[serde(default)]
+		pub formal_name: Option<String>,
+		pub start_time: Option<String>,
+	}
+
+	impl Contest {
+		pub fn title(&self) -> String {
+			self.formal_name
+				.clone()
+				.filter(|name| !name.is_empty())
+				.unwrap_or_else(|| self.name.clone())
+		}
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Problem {
+		pub id: Id,
+		pub label: String,
+		pub name: String,
+		#[serde(default)]
+		pub ordinal: i64,
+		/// Time limit in seconds.
+		#[serde(default)]
+		pub time_limit: Option<f64>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Language {
+		pub id: Id,
+		pub name: String,
+		#[serde(default)]
+		pub extensions: Vec<String>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Submission {
+		pub id: Id,
+		pub problem_id: Id,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Judgement {
+		pub submission_id: Id,
+		pub judgement_type_id: Option<String>,
+		#[serde(default = "default_valid")]
+		pub valid: bool,
+	}
+
+	#[derive(Debug, Deserialize)]
+	pub struct Created {
+		pub id: Id,
+	}
+
+	fn default_valid() -> bool {
+		true
+	}
+}
@@ -0,0 +1,96 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Verdict};
+use unijudge_domjudge::DOMjudge;
+
+const SITE_URL: &str = "https://judge.example.com/domjudge";
+const SITE: TestSite<DOMjudge> = TestSite {
+	backend: &DOMjudge,
+	domain: "judge.example.com",
+	manifest_dir: env!("CARGO_MANIFEST_DIR"),
+};
+
+/// DOMjudge URLs are only recognized after the server is configured.
+fn site() -> &'static TestSite<DOMjudge> {
+	unijudge_domjudge::set_sites(vec![SITE_URL]).unwrap();
+	&SITE
+}
+
+#[test]
+fn test_deconstruct_url() {
+	let session = site().session("contests.json");
+	let url = "https://judge.example.com/domjudge/api/v4/contests/3/problems/sum";
+	let team_url = "https://judge.example.com/domjudge/team/problems/sum?contest=3";
+	assert_eq!(DOMjudge.task_url(&session, &site().task(url)).unwrap(), team_url);
+	assert_eq!(DOMjudge.task_url(&session, &site().task(team_url)).unwrap(), team_url);
+	let task = site().task("https://judge.example.com/domjudge/api/contests/3/problems/sum");
+	assert_eq!(DOMjudge.contest_id(&DOMjudge.task_contest(&task).unwrap()), "3");
+	assert_eq!(
+		DOMjudge.submission_url(&session, &task, "12"),
+		"https://judge.example.com/domjudge/team/submission/12"
+	);
+	let url = "https://judge.example.com/domjudge/team/problems/sum";
+	assert!(DOMjudge.deconstruct_url(url).is_err());
+	let url = "https://judge.example.com/domjudge/api/v4/contests/3";
+	assert_eq!(DOMjudge.contest_url(&site().contest(&format!("{}/", url))), url);
+	assert!(DOMjudge.deconstruct_url("https://judge.example.com/domjudge/team").is_err());
+	assert!(DOMjudge.deconstruct_url("https://judge.example.com/api/v4/contests/3").is_err());
+	let url = "https://example.com/domjudge/api/v4/contests/3";
+	assert!(DOMjudge.deconstruct_url(url).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = DOMjudge.contests(&site().session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 2);
+	assert_eq!(DOMjudge.contest_id(&contests[0].id), "3");
+	assert_eq!(contests[0].title, "Practice Session");
+	assert_eq!(contests[0].start.unwrap().to_rfc3339(), "2020-02-10T16:00:00+01:00");
+	assert_eq!(DOMjudge.contest_id(&contests[1].id), "4");
+	assert_eq!(contests[1].title, "Training 2");
+}
+
+#[tokio::test]
+async fn test_contest_tasks() {
+	let session = site().session("contest_tasks.json");
+	let contest = site().contest("https://judge.example.com/domjudge/api/v4/contests/3");
+	let tasks = DOMjudge.contest_tasks(&session, &contest).await.unwrap();
+	let urls =
+		tasks.iter().map(|task| DOMjudge.task_url(&session, task).unwrap()).collect::<Vec<_>>();
+	assert_eq!(urls, vec![
+		"https://judge.example.com/domjudge/team/problems/sum?contest=3",
+		"https://judge.example.com/domjudge/team/problems/hello?contest=3",
+	]);
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = site().task("https://judge.example.com/domjudge/api/v4/contests/3/problems/sum");
+	let details = DOMjudge.task_details(&site().session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "A");
+	assert_eq!(details.title, "A Plus B");
+	assert_eq!(details.contest_id, "3");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_millis(2500)));
+	assert!(details.statement.is_none());
+	let examples = details.examples.unwrap();
+	let examples =
+		examples.iter().map(|e| (e.input.as_str(), e.output.as_str())).collect::<Vec<_>>();
+	assert_eq!(examples, vec![("1 2\n", "3\n"), ("2 3\n", "5\n"), ("100 200\n", "300\n")]);
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = site().session("task_submissions.json");
+	let auth = DOMjudge.auth_deserialize(r#"{"username":"team1","password":"hunter2"}"#).unwrap();
+	DOMjudge.auth_restore(&session, &auth).await.unwrap();
+	let task = site().task("https://judge.example.com/domjudge/api/v4/contests/3/problems/sum");
+	let submissions = DOMjudge.task_submissions(&session, &task).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "104");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "103");
+	assert_eq!(submissions[1].verdict, Verdict::Accepted);
+	assert_eq!(submissions[2].id, "101");
+	assert_eq!(submissions[2].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+}
@@ -0,0 +1,425 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use unijudge::{
+	debris::{self, Context, Find}, http::{Client, Cookie}, reqwest::{multipart, StatusCode, Url}, ContestDetails, Error, Example, Language, RejectionCause, Resource, Result, Submission, TaskDetails, Verdict
+};
+
+const SITE: &str = "https://open.kattis.com";
+
+#[derive(Debug)]
+pub struct Kattis;
+
+#[derive(Debug)]
+pub struct Task {
+	contest: Option<String>,
+	problem: String,
+}
+
+#[derive(Debug)]
+pub struct Session {
+	client: Client,
+	username: Mutex<Option<String>>,
+}
+
+/// The username is kept alongside the session cookie, because submissions are listed on the user's
+/// own pages.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedAuth {
+	edu_site_cookie: Cookie,
+	username: String,
+}
+
+/// Languages accepted by the submission endpoint, together with the file extension and main class
+/// Kattis needs to compile a solution.
+const LANGUAGES: &[(&str, &str, &str)] = &[
+	("C", "c", ""),
+	("C#", "cs", ""),
+	("C++", "cpp", ""),
+	("Go", "go", ""),
+	("Haskell", "hs", ""),
+	("Java", "java", "Main"),
+	("JavaScript", "js", ""),
+	("Kotlin", "kt", "MainKt"),
+	("Python 2", "py", ""),
+	("Python 3", "py", ""),
+	("Rust", "rs", ""),
+];
+
+#[async_trait(?Send)]
+impl unijudge::Backend for Kattis {
+	type CachedAuth = CachedAuth;
+	type Contest = String;
+	type Session = Session;
+	type Task = Task;
+
+	fn accepted_domains(&self) -> &'static [&'static str] {
+		&["open.kattis.com"]
+	}
+
+	fn deconstruct_resource(
+		&self,
+		_domain: &str,
+		segments: &[&str],
+	) -> Result<Resource<Self::Contest, Self::Task>>
+	{
+		match segments {
+			["problems", problem] => {
+				Ok(Resource::Task(Task { contest: None, problem: (*problem).to_owned() }))
+			},
+			["contests", contest] | ["contests", contest, "problems"] => {
+				Ok(Resource::Contest((*contest).to_owned()))
+			},
+			["contests", contest, "problems", problem] => Ok(Resource::Task(Task {
+				contest: Some((*contest).to_owned()),
+				problem: (*problem).to_owned(),
+			})),
+			_ => Err(Error::WrongTaskUrl),
+		}
+	}
+
+	fn connect(&self, client: Client, _domain: &str) -> Self::Session {
+		Session { client, username: Mutex::new(None) }
+	}
+
+	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
+		let username = session.username.lock().map_err(|_| Error::StateCorruption)?.clone();
+		let edu_site_cookie = session.client.cookie_get("EduSiteCookie")?;
+		Ok(match (edu_site_cookie, username) {
+			(Some(edu_site_cookie), Some(username)) => {
+				Some(CachedAuth { edu_site_cookie, username })
+			},
+			_ => None,
+		})
+	}
+
+	fn auth_deserialize(&self, data: &str) -> Result<Self::CachedAuth> {
+		unijudge::deserialize_auth(data)
+	}
+
+	async fn auth_login(
+		&self,
+		session: &Self::Session,
+		username: &str,
+		password: &str,
+	) -> Result<()>
+	{
+		// This is the endpoint used by the official command-line client, which responds with plain
+		// text instead of redirecting to the user profile.
+		let resp = session
+			.client
+			.post("https://open.kattis.com/login".parse()?)
+			.form(&[("user", username), ("password", password), ("script", "true")])
+			.send()
+			.await?;
+		let status = resp.status();
+		let text = resp.text().await?;
+		if status == StatusCode::FORBIDDEN {
+			Err(Error::WrongCredentials)
+		} else if text.contains("Login successful") {
+			*session.username.lock().map_err(|_| Error::StateCorruption)? =
+				Some(username.to_owned());
+			Ok(())
+		} else {
+			Err(Error::UnexpectedResponse {
+				endpoint: "/login",
+				message: "unrecognized login outcome",
+				resp_raw: text,
+				inner: None,
+			})
+		}
+	}
+
+	async fn auth_restore(&self, session: &Self::Session, auth: &Self::CachedAuth) -> Result<()> {
+		session.client.cookie_set(auth.edu_site_cookie.clone(), "https://open.kattis.com/")?;
+		*session.username.lock().map_err(|_| Error::StateCorruption)? = Some(auth.username.clone());
+		Ok(())
+	}
+
+	fn auth_serialize(&self, auth: &Self::CachedAuth) -> Result<String> {
+		unijudge::serialize_auth(auth)
+	}
+
+	fn task_contest(&self, task: &Self::Task) -> Option<Self::Contest> {
+		task.contest.clone()
+	}
+
+	async fn task_details(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<TaskDetails>
+	{
+		let url: Url = self.task_url(session, task)?.parse()?;
+		let resp = session.client.get(url.clone()).send().await?;
+		if resp.status() == StatusCode::NOT_FOUND {
+			return Err(Error::WrongData);
+		}
+		let doc = debris::Document::new(&resp.text().await?);
+		let title = doc.find_first("h1")?.text().string();
+		let mut time_limit = None;
+		let mut memory_limit = None;
+		for property in doc.find_all(".problem-sidebar p") {
+			let property = property.text().string();
+			if property.starts_with("CPU Time limit") {
+				time_limit = unijudge::parse_time_limit(&property);
+			} else if property.starts_with("Memory limit") {
+				memory_limit = unijudge::parse_memory_limit(&property);
+			}
+		}
+		// Interactive samples are shown as a three-column conversation instead of input and output.
+		let interactive = doc
+			.find_all("table.sample th")
+			.any(|header| header.text().string().contains("Interaction"));
+		let examples = if interactive {
+			None
+		} else {
+			Some(
+				doc.find_all("table.sample")
+					.map(|table| {
+						Ok(Example {
+							input: table.find_nth("pre", 0)?.text().string(),
+							output: table.find_nth("pre", 1)?.text().string(),
+						})
+					})
+					.collect::<debris::Result<_>>()?,
+			)
+		};
+		let mut statement = unijudge::statement::Rewrite::start(doc);
+		statement.fix_hide(|v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				["headline-wrapper", "problembody"].iter().any(|class| {
+					v.has_class(class, unijudge::selectors::attr::CaseSensitivity::CaseSensitive)
+				})
+			} else {
+				false
+			}
+		});
+		statement.fix_override_csp();
+		statement.fix_traverse(|mut v| {
+			if let unijudge::scraper::Node::Element(v) = v.value() {
+				unijudge::statement::fix_url(v, unijudge::qn!("href"), "//", "https:");
+				unijudge::statement::fix_url(v, unijudge::qn!("src"), "//", "https:");
+				unijudge::statement::fix_url(v, unijudge::qn!("href"), "/", SITE);
+				unijudge::statement::fix_url(v, unijudge::qn!("src"), "/", SITE);
+			}
+		});
+		Ok(TaskDetails {
+			id: task.problem.clone(),
+			title,
+			contest_id: task.contest.clone().unwrap_or_else(|| "problems".to_owned()),
+			site_short: "kattis".to_owned(),
+			examples,
+			statement: Some(statement.export()),
+			url: url.to_string(),
+			time_limit,
+			memory_limit,
+			input_file: None,
+			output_file: None,
+			interactive,
+		})
+	}
+
+	async fn task_languages(
+		&self,
+		_session: &Self::Session,
+		_task: &Self::Task,
+	) -> Result<Vec<Language>>
+	{
+		Ok(LANGUAGES
+			.iter()
+			.map(|(name, _, _)| Language { id: (*name).to_owned(), name: (*name).to_owned() })
+			.collect())
+	}
+
+	async fn task_submissions(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Submission>>
+	{
+		let url: Url = format!(
+			"https://open.kattis.com/users/{}/submissions/{}",
+			session.req_user()?,
+			task.problem
+		)
+		.parse()?;
+		let resp = session.client.get(url).send().await?;
+		if resp.status() == StatusCode::FORBIDDEN || resp.url().path() == "/login" {
+			return Err(Error::AccessDenied);
+		}
+		let doc = debris::Document::new(&resp.text().await?);
+		Ok(doc
+			.find_all("tr[data-submission-id]")
+			.map(|row| {
+				Ok(Submission {
+					id: row.attr("data-submission-id")?.string(),
+					verdict: row.find("td.status")?.text().map(|status| match status {
+						"New"
+						| "Waiting for compile"
+						| "Compiling"
+						| "Waiting for run"
+						| "Running" => Ok(Verdict::Pending { test: None }),
+						"Accepted" => Ok(Verdict::Accepted),
+						"Wrong Answer" => Ok(rejected(RejectionCause::WrongAnswer)),
+						"Time Limit Exceeded" => Ok(rejected(RejectionCause::TimeLimitExceeded)),
+						"Memory Limit Exceeded" => {
+							Ok(rejected(RejectionCause::MemoryLimitExceeded))
+						},
+						"Run Time Error" => Ok(rejected(RejectionCause::RuntimeError)),
+						"Compile Error" => Ok(rejected(RejectionCause::CompilationError)),
+						"Judge Error" => Ok(rejected(RejectionCause::SystemError)),
+						"Illegal Function" => Ok(rejected(RejectionCause::RuleViolation)),
+						// Output limit exceeded does not have a matching cause.
+						"Output Limit Exceeded" => {
+							Ok(Verdict::Rejected { cause: None, test: None })
+						},
+						_ => Err(format!("unrecognized Kattis verdict {:?}", status)),
+					})?,
+					language: None,
+					time: None,
+				})
+			})
+			.collect::<Result<_>>()?)
+	}
+
+	async fn task_submit(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+	) -> Result<String>
+	{
+		let (_, extension, main_class) =
+			LANGUAGES.iter().find(|(name, _, _)| *name == language.id).ok_or(Error::WrongData)?;
+		let file = multipart::Part::bytes(code.as_bytes().to_owned())
+			.file_name(format!("solution.{}", extension));
+		let form = multipart::Form::new()
+			.text("submit", "true")
+			.text("submit_ctr", "2")
+			.text("language", language.id.clone())
+			.text("mainclass", *main_class)
+			.text("problem", task.problem.clone())
+			.text("tag", "")
+			.text("script", "true")
+			.part("sub_file[]", file);
+		let resp = session
+			.client
+			.post("https://open.kattis.com/submit".parse()?)
+			.multipart(form)
+			.send()
+			.await?;
+		if resp.status() == StatusCode::FORBIDDEN {
+			return Err(Error::AccessDenied);
+		}
+		// The response is a plain text message like "Submission received. Submission ID: 123.".
+		let text = resp.text().await?;
+		let id = match text.find("Submission ID: ") {
+			Some(i) => text[i + 15..].chars().take_while(char::is_ascii_digit).collect::<String>(),
+			None => String::new(),
+		};
+		if id.is_empty() {
+			return Err(Error::UnexpectedResponse {
+				endpoint: "/submit",
+				message: "submission ID not found",
+				resp_raw: text,
+				inner: None,
+			});
+		}
+		Ok(id)
+	}
+
+	fn task_url(&self, _session: &Self::Session, task: &Self::Task) -> Result<String> {
+		Ok(match &task.contest {
+			Some(contest) => format!("{}/problems/{}", self.contest_url(contest), task.problem),
+			None => format!("https://open.kattis.com/problems/{}", task.problem),
+		})
+	}
+
+	fn submission_url(&self, _session: &Self::Session, _task: &Self::Task, id: &str) -> String {
+		format!("https://open.kattis.com/submissions/{}", id)
+	}
+
+	fn contest_id(&self, contest: &Self::Contest) -> String {
+		contest.clone()
+	}
+
+	fn contest_site_prefix(&self) -> &'static str {
+		"Kattis"
+	}
+
+	async fn contest_tasks(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Self::Task>>
+	{
+		let url: Url = format!("{}/problems", self.contest_url(contest)).parse()?;
+		let resp = session.client.get(url).send().await?;
+		if resp.status() == StatusCode::NOT_FOUND {
+			return Err(Error::WrongData);
+		}
+		let doc = debris::Document::new(&resp.text().await?);
+		let prefix = format!("/contests/{}/problems/", contest);
+		let mut tasks: Vec<Task> = Vec::new();
+		for link in doc.find_all("table a[href]") {
+			let href = link.attr("href")?.string();
+			if href.starts_with(&prefix) {
+				let problem = &href[prefix.len()..];
+				if !problem.contains('/') && tasks.iter().all(|task| task.problem != problem) {
+					let problem = problem.to_owned();
+					tasks.push(Task { contest: Some(contest.clone()), problem });
+				}
+			}
+		}
+		// The problem list is empty until the contest starts.
+		if tasks.is_empty() {
+			return Err(Error::NotYetStarted);
+		}
+		Ok(tasks)
+	}
+
+	fn contest_url(&self, contest: &Self::Contest) -> String {
+		format!("https://open.kattis.com/contests/{}", contest)
+	}
+
+	async fn contest_title(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<String>
+	{
+		let url: Url = self.contest_url(contest).parse()?;
+		let doc = debris::Document::new(&session.client.get(url).send().await?.text().await?);
+		Ok(doc.find_first("h1, h2")?.text().string())
+	}
+
+	async fn contests(
+		&self,
+		_session: &Self::Session,
+	) -> Result<Vec<ContestDetails<Self::Contest>>>
+	{
+		// Kattis contests are mostly private sessions created by users, so listing them is not
+		// useful.
+		Ok(Vec::new())
+	}
+
+	fn name_short(&self) -> &'static str {
+		"kattis"
+	}
+
+	fn supports_contests(&self) -> bool {
+		false
+	}
+}
+
+impl Session {
+	fn req_user(&self) -> Result<String> {
+		self.username.lock().map_err(|_| Error::StateCorruption)?.clone().ok_or(Error::AccessDenied)
+	}
+}
+
+fn rejected(cause: RejectionCause) -> Verdict {
+	Verdict::Rejected { cause: Some(cause), test: None }
+}
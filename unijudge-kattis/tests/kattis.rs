@@ -0,0 +1,74 @@
+use unijudge::{testing::TestSite, Backend, Error, Language, RejectionCause, Verdict};
+use unijudge_kattis::Kattis;
+
+const SITE: TestSite<Kattis> =
+	TestSite { backend: &Kattis, domain: "open.kattis.com", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	for url in &[
+		"https://open.kattis.com/problems/hello",
+		"https://open.kattis.com/contests/ncpc19/problems/circuitmath",
+	] {
+		assert_eq!(Kattis.task_url(&session, &SITE.task(url)).unwrap(), *url);
+	}
+	let task = SITE.task("https://open.kattis.com/contests/ncpc19/problems/circuitmath");
+	assert_eq!(Kattis.task_contest(&task), Some("ncpc19".to_owned()));
+	assert_eq!(SITE.contest("https://open.kattis.com/contests/ncpc19"), "ncpc19");
+	assert!(Kattis.deconstruct_url("https://open.kattis.com/help/cpp").is_err());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = SITE.task("https://open.kattis.com/problems/hello");
+	let details = Kattis.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "hello");
+	assert_eq!(details.title, "Hello World!");
+	assert_eq!(details.contest_id, "problems");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(1)));
+	assert_eq!(details.memory_limit, Some(1024 * 1024 * 1024));
+	assert!(!details.interactive);
+	let examples = details.examples.unwrap();
+	assert_eq!(examples.len(), 1);
+	assert_eq!(examples[0].input, "");
+	assert_eq!(examples[0].output, "Hello World!\n");
+}
+
+#[tokio::test]
+async fn test_contest_tasks() {
+	let session = SITE.session("contest_tasks.json");
+	let contest = SITE.contest("https://open.kattis.com/contests/ncpc19");
+	let tasks = Kattis.contest_tasks(&session, &contest).await.unwrap();
+	let urls =
+		tasks.iter().map(|task| Kattis.task_url(&session, task).unwrap()).collect::<Vec<_>>();
+	assert_eq!(urls, vec![
+		"https://open.kattis.com/contests/ncpc19/problems/amazingadventures",
+		"https://open.kattis.com/contests/ncpc19/problems/bobbythebike",
+		"https://open.kattis.com/contests/ncpc19/problems/circuitmath",
+	]);
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = SITE.session("task_submissions.json");
+	let task = SITE.task("https://open.kattis.com/problems/hello");
+	match Kattis.task_submissions(&session, &task).await {
+		Err(Error::AccessDenied) => (),
+		other => panic!("expected access to be denied, got {:?}", other),
+	}
+	Kattis.auth_login(&session, "tester", "hunter2").await.unwrap();
+	let language = Language { id: "C++".to_owned(), name: "C++".to_owned() };
+	let first = Kattis.task_submit(&session, &task, &language, "int main() {}").await.unwrap();
+	let second = Kattis.task_submit(&session, &task, &language, "int main() {}").await.unwrap();
+	assert_eq!((first.as_str(), second.as_str()), ("5184203", "5184217"));
+	let submissions = Kattis.task_submissions(&session, &task).await.unwrap();
+	assert_eq!(submissions.len(), 2);
+	assert_eq!(submissions[0].id, "5184217");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "5184203");
+	assert_eq!(submissions[1].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+}
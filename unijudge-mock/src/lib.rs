@@ -0,0 +1,487 @@
+//! Imaginary judge that runs entirely in memory and behaves according to a scripted [`Scenario`].
+//! It is registered only when ICIE is built with the `mock` feature, so that flows like waiting for
+//! a contest, logging in and tracking submissions can be tested without a real site.
+
+use async_trait::async_trait;
+use std::{
+	cell::RefCell, sync::{Mutex, MutexGuard}
+};
+use unijudge::{
+	chrono::{DateTime, Duration, FixedOffset, Utc}, http::Client, ContestDetails, Error, Example, Language, Resource, Result, Statement, Submission, TaskDetails, Verdict
+};
+
+/// Domain of the imaginary judge. The .invalid top-level domain is reserved, so no real site can
+/// ever be mistaken for it.
+pub const DOMAIN: &str = "mock.icie.invalid";
+
+#[derive(Debug)]
+pub struct Mock;
+
+#[derive(Debug)]
+pub struct Task {
+	contest: String,
+	task: String,
+}
+
+#[derive(Debug)]
+pub struct Session {
+	username: Mutex<Option<String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scenario {
+	/// Credentials accepted when logging in.
+	pub username: String,
+	pub password: String,
+	/// Whether languages, submissions and submitting are denied until the user logs in.
+	pub require_login: bool,
+	pub contests: Vec<MockContest>,
+	/// Verdicts every submission goes through, advancing by one each time submissions are listed.
+	/// The last verdict is the final one.
+	pub verdicts: Vec<Verdict>,
+	pub failures: Vec<Failure>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MockContest {
+	pub id: String,
+	pub title: String,
+	/// Contest start relative to the moment the scenario was set. Negative values describe
+	/// contests that have already started.
+	pub start: Duration,
+	pub tasks: Vec<MockTask>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MockTask {
+	pub id: String,
+	pub title: String,
+	pub examples: Vec<Example>,
+}
+
+/// Error returned by the first few calls of an operation, before it starts working normally.
+#[derive(Clone, Debug)]
+pub struct Failure {
+	pub operation: Operation,
+	pub kind: FailureKind,
+	pub count: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+	Login,
+	TaskDetails,
+	TaskLanguages,
+	TaskSubmissions,
+	TaskSubmit,
+	ContestTasks,
+	ContestTitle,
+	Contests,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureKind {
+	NetworkFailure,
+	RateLimit,
+}
+
+/// State of the imaginary site, shared by all sessions like on a real judge, so that submissions
+/// made in one session can be tracked in another.
+#[derive(Debug)]
+struct Site {
+	scenario: Scenario,
+	created: DateTime<Utc>,
+	submissions: Vec<MockSubmission>,
+}
+
+#[derive(Debug)]
+struct MockSubmission {
+	id: String,
+	contest: String,
+	task: String,
+	progress: usize,
+}
+
+thread_local! {
+	static SITE: RefCell<Option<Site>> = RefCell::new(None);
+}
+
+/// Replaces the scenario followed by the site on this thread, removing all submissions made so far.
+/// The default scenario is used until this is called.
+pub fn set_scenario(scenario: Scenario) {
+	SITE.with(|site| *site.borrow_mut() = Some(Site::new(scenario)));
+}
+
+fn with_site<T>(f: impl FnOnce(&mut Site) -> Result<T>) -> Result<T> {
+	SITE.with(|site| {
+		let mut site = site.try_borrow_mut().map_err(|_| Error::StateCorruption)?;
+		f(site.get_or_insert_with(|| Site::new(Scenario::default())))
+	})
+}
+
+#[async_trait(?Send)]
+impl unijudge::Backend for Mock {
+	type CachedAuth = String;
+	type Contest = String;
+	type Session = Session;
+	type Task = Task;
+
+	fn accepted_domains(&self) -> &'static [&'static str] {
+		&[DOMAIN]
+	}
+
+	fn deconstruct_resource(
+		&self,
+		_domain: &str,
+		segments: &[&str],
+	) -> Result<Resource<Self::Contest, Self::Task>>
+	{
+		match segments {
+			["contest", contest] => Ok(Resource::Contest((*contest).to_owned())),
+			["contest", contest, "problem", task] => Ok(Resource::Task(Task {
+				contest: (*contest).to_owned(),
+				task: (*task).to_owned(),
+			})),
+			_ => Err(Error::WrongTaskUrl),
+		}
+	}
+
+	fn connect(&self, _client: Client, _domain: &str) -> Self::Session {
+		Session { username: Mutex::new(None) }
+	}
+
+	async fn auth_cache(&self, session: &Self::Session) -> Result<Option<Self::CachedAuth>> {
+		Ok(session.username()?.clone())
+	}
+
+	fn auth_deserialize(&self, data: &str) -> Result<Self::CachedAuth> {
+		unijudge::deserialize_auth(data)
+	}
+
+	async fn auth_login(
+		&self,
+		session: &Self::Session,
+		username: &str,
+		password: &str,
+	) -> Result<()>
+	{
+		session.enter(Operation::Login)?;
+		with_site(|site| {
+			if username == site.scenario.username && password == site.scenario.password {
+				*session.username()? = Some(username.to_owned());
+				Ok(())
+			} else {
+				Err(Error::WrongCredentials)
+			}
+		})
+	}
+
+	async fn auth_restore(&self, session: &Self::Session, auth: &Self::CachedAuth) -> Result<()> {
+		with_site(|site| {
+			if *auth == site.scenario.username {
+				*session.username()? = Some(auth.clone());
+				Ok(())
+			} else {
+				Err(Error::WrongCredentials)
+			}
+		})
+	}
+
+	fn auth_serialize(&self, auth: &Self::CachedAuth) -> Result<String> {
+		unijudge::serialize_auth(auth)
+	}
+
+	fn task_contest(&self, task: &Self::Task) -> Option<Self::Contest> {
+		Some(task.contest.clone())
+	}
+
+	async fn task_details(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<TaskDetails>
+	{
+		session.enter(Operation::TaskDetails)?;
+		let url = self.task_url(session, task)?;
+		with_site(|site| {
+			let contest = site.started_contest(&task.contest)?;
+			let details =
+				contest.tasks.iter().find(|t| t.id == task.task).ok_or(Error::WrongData)?;
+			Ok(TaskDetails {
+				id: details.id.clone(),
+				title: details.title.clone(),
+				contest_id: contest.id.clone(),
+				site_short: "mock".to_owned(),
+				examples: Some(details.examples.clone()),
+				statement: Some(Statement::HTML {
+					html: format!(
+						"<html><body><h1>{}. {}</h1><p>Mock statement.</p></body></html>",
+						details.id, details.title
+					),
+				}),
+				url,
+				time_limit: Some(std::time::Duration::from_secs(1)),
+				memory_limit: Some(256 * 1024 * 1024),
+				input_file: None,
+				output_file: None,
+				interactive: false,
+			})
+		})
+	}
+
+	async fn task_languages(
+		&self,
+		session: &Self::Session,
+		_task: &Self::Task,
+	) -> Result<Vec<Language>>
+	{
+		session.enter(Operation::TaskLanguages)?;
+		with_site(|site| session.require_login(site))?;
+		Ok(languages())
+	}
+
+	async fn task_submissions(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+	) -> Result<Vec<Submission>>
+	{
+		session.enter(Operation::TaskSubmissions)?;
+		with_site(|site| {
+			session.require_login(site)?;
+			let Site { scenario, submissions, .. } = site;
+			Ok(submissions
+				.iter_mut()
+				.rev()
+				.filter(|submission| {
+					submission.contest == task.contest && submission.task == task.task
+				})
+				.map(|submission| {
+					let verdict = match scenario.verdicts.get(submission.progress) {
+						Some(verdict) => verdict.clone(),
+						None => scenario.verdicts.last().cloned().unwrap_or(Verdict::Accepted),
+					};
+					submission.progress += 1;
+					Submission { id: submission.id.clone(), verdict, language: None, time: None }
+				})
+				.collect())
+		})
+	}
+
+	async fn task_submit(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		_code: &str,
+	) -> Result<String>
+	{
+		session.enter(Operation::TaskSubmit)?;
+		with_site(|site| {
+			session.require_login(site)?;
+			site.started_contest(&task.contest)?;
+			if languages().iter().all(|known| known.id != language.id) {
+				return Err(Error::WrongData);
+			}
+			let id = (site.submissions.len() + 1).to_string();
+			site.submissions.push(MockSubmission {
+				id: id.clone(),
+				contest: task.contest.clone(),
+				task: task.task.clone(),
+				progress: 0,
+			});
+			Ok(id)
+		})
+	}
+
+	fn task_url(&self, _session: &Self::Session, task: &Self::Task) -> Result<String> {
+		Ok(format!("{}/problem/{}", self.contest_url(&task.contest), task.task))
+	}
+
+	fn submission_url(&self, _session: &Self::Session, task: &Self::Task, id: &str) -> String {
+		format!("{}/submission/{}", self.contest_url(&task.contest), id)
+	}
+
+	fn contest_id(&self, contest: &Self::Contest) -> String {
+		contest.clone()
+	}
+
+	fn contest_site_prefix(&self) -> &'static str {
+		"Mock"
+	}
+
+	async fn contest_tasks(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Self::Task>>
+	{
+		session.enter(Operation::ContestTasks)?;
+		with_site(|site| {
+			Ok(site
+				.started_contest(contest)?
+				.tasks
+				.iter()
+				.map(|task| Task { contest: contest.clone(), task: task.id.clone() })
+				.collect())
+		})
+	}
+
+	fn contest_url(&self, contest: &Self::Contest) -> String {
+		format!("https://{}/contest/{}", DOMAIN, contest)
+	}
+
+	async fn contest_title(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<String>
+	{
+		session.enter(Operation::ContestTitle)?;
+		with_site(|site| Ok(site.contest(contest)?.title.clone()))
+	}
+
+	async fn contests(
+		&self,
+		session: &Self::Session,
+	) -> Result<Vec<ContestDetails<Self::Contest>>>
+	{
+		session.enter(Operation::Contests)?;
+		with_site(|site| {
+			Ok(site
+				.scenario
+				.contests
+				.iter()
+				.map(|contest| ContestDetails {
+					id: contest.id.clone(),
+					title: contest.title.clone(),
+					start: Some(site.start(contest).with_timezone(&FixedOffset::east(0))),
+				})
+				.collect())
+		})
+	}
+
+	fn name_short(&self) -> &'static str {
+		"mock"
+	}
+
+	fn supports_contests(&self) -> bool {
+		true
+	}
+}
+
+impl Session {
+	fn username(&self) -> Result<MutexGuard<Option<String>>> {
+		self.username.lock().map_err(|_| Error::StateCorruption)
+	}
+
+	/// Returns the scripted error, if the operation is supposed to fail this time.
+	fn enter(&self, operation: Operation) -> Result<()> {
+		match with_site(|site| Ok(site.take_failure(operation)))? {
+			Some(FailureKind::NetworkFailure) => Err(Error::SimulatedNetworkFailure),
+			Some(FailureKind::RateLimit) => Err(Error::RateLimit { retry_after: None }),
+			None => Ok(()),
+		}
+	}
+
+	fn require_login(&self, site: &Site) -> Result<()> {
+		if site.scenario.require_login && self.username()?.is_none() {
+			Err(Error::AccessDenied)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl Site {
+	fn new(scenario: Scenario) -> Site {
+		Site { scenario, created: Utc::now(), submissions: Vec::new() }
+	}
+
+	fn take_failure(&mut self, operation: Operation) -> Option<FailureKind> {
+		let failure = self
+			.scenario
+			.failures
+			.iter_mut()
+			.find(|failure| failure.operation == operation && failure.count > 0)?;
+		failure.count -= 1;
+		Some(failure.kind)
+	}
+
+	fn contest(&self, id: &str) -> Result<&MockContest> {
+		self.scenario.contests.iter().find(|contest| contest.id == id).ok_or(Error::WrongData)
+	}
+
+	fn started_contest(&self, id: &str) -> Result<&MockContest> {
+		let contest = self.contest(id)?;
+		if Utc::now() < self.start(contest) {
+			return Err(Error::NotYetStarted);
+		}
+		Ok(contest)
+	}
+
+	fn start(&self, contest: &MockContest) -> DateTime<Utc> {
+		self.created + contest.start
+	}
+}
+
+fn languages() -> Vec<Language> {
+	vec![
+		Language { id: "cpp".to_owned(), name: "C++".to_owned() },
+		Language { id: "py".to_owned(), name: "Python 3".to_owned() },
+	]
+}
+
+impl Default for Scenario {
+	/// A contest that started an hour ago, another one starting in half a minute, and submissions
+	/// that are judged for a while and then accepted. The first attempt to list submissions fails
+	/// with a network error, so that retrying can be seen as well.
+	fn default() -> Scenario {
+		let example = |input: &str, output: &str| Example {
+			input: input.to_owned(),
+			output: output.to_owned(),
+		};
+		let task = |id: &str, title: &str, examples: Vec<Example>| MockTask {
+			id: id.to_owned(),
+			title: title.to_owned(),
+			examples,
+		};
+		Scenario {
+			username: "tester".to_owned(),
+			password: "tester".to_owned(),
+			require_login: true,
+			contests: vec![
+				MockContest {
+					id: "running".to_owned(),
+					title: "Mock Running Round".to_owned(),
+					start: Duration::hours(-1),
+					tasks: vec![
+						task("A", "Sum", vec![example("1 2\n", "3\n"), example("5 7\n", "12\n")]),
+						task("B", "Maximum", vec![example("3\n1 3 2\n", "3\n")]),
+					],
+				},
+				MockContest {
+					id: "upcoming".to_owned(),
+					title: "Mock Upcoming Round".to_owned(),
+					start: Duration::seconds(30),
+					tasks: vec![
+						task("A", "Echo", vec![example("icie\n", "icie\n")]),
+						task("B", "Reverse", vec![example("abc\n", "cba\n")]),
+						task("C", "Count", vec![example("a b c\n", "3\n")]),
+					],
+				},
+			],
+			verdicts: vec![
+				Verdict::Pending { test: None },
+				Verdict::Pending { test: Some("test 1".to_owned()) },
+				Verdict::Pending { test: Some("test 2".to_owned()) },
+				Verdict::Accepted,
+			],
+			failures: vec![Failure {
+				operation: Operation::TaskSubmissions,
+				kind: FailureKind::NetworkFailure,
+				count: 1,
+			}],
+		}
+	}
+}
@@ -0,0 +1,111 @@
+use unijudge::{chrono::Duration, testing::TestSite, Backend, Error, Verdict};
+use unijudge_mock::{
+	set_scenario, Failure, FailureKind, Mock, MockContest, MockTask, Operation, Scenario, DOMAIN
+};
+
+const SITE: TestSite<Mock> =
+	TestSite { backend: &Mock, domain: DOMAIN, manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+#[tokio::test]
+async fn test_submission_progress() {
+	set_scenario(Scenario { failures: Vec::new(), ..Scenario::default() });
+	let session = SITE.offline_session();
+	let task = SITE.task("https://mock.icie.invalid/contest/running/problem/A");
+	match Mock.task_languages(&session, &task).await {
+		Err(Error::AccessDenied) => (),
+		other => panic!("expected access to be denied, got {:?}", other),
+	}
+	Mock.auth_login(&session, "tester", "tester").await.unwrap();
+	let language = Mock.task_languages(&session, &task).await.unwrap().remove(0);
+	let id = Mock.task_submit(&session, &task, &language, "int main() {}").await.unwrap();
+	let mut verdicts = Vec::new();
+	for _ in 0..5 {
+		let submissions = Mock.task_submissions(&session, &task).await.unwrap();
+		assert_eq!(submissions[0].id, id);
+		verdicts.push(submissions[0].verdict.clone());
+	}
+	assert_eq!(verdicts, vec![
+		Verdict::Pending { test: None },
+		Verdict::Pending { test: Some("test 1".to_owned()) },
+		Verdict::Pending { test: Some("test 2".to_owned()) },
+		Verdict::Accepted,
+		Verdict::Accepted,
+	]);
+}
+
+#[tokio::test]
+async fn test_submissions_shared_by_sessions() {
+	set_scenario(Scenario { failures: Vec::new(), ..Scenario::default() });
+	let task = SITE.task("https://mock.icie.invalid/contest/running/problem/B");
+	let first = SITE.offline_session();
+	Mock.auth_login(&first, "tester", "tester").await.unwrap();
+	let language = Mock.task_languages(&first, &task).await.unwrap().remove(0);
+	let id = Mock.task_submit(&first, &task, &language, "int main() {}").await.unwrap();
+	let second = SITE.offline_session();
+	match Mock.task_submissions(&second, &task).await {
+		Err(Error::AccessDenied) => (),
+		other => panic!("expected access to be denied, got {:?}", other),
+	}
+	let auth = Mock.auth_cache(&first).await.unwrap().unwrap();
+	Mock.auth_restore(&second, &auth).await.unwrap();
+	let submissions = Mock.task_submissions(&second, &task).await.unwrap();
+	assert_eq!(submissions.len(), 1);
+	assert_eq!(submissions[0].id, id);
+}
+
+#[tokio::test]
+async fn test_failures() {
+	set_scenario(Scenario {
+		require_login: false,
+		failures: vec![
+			Failure { operation: Operation::ContestTitle, kind: FailureKind::RateLimit, count: 2 },
+			Failure {
+				operation: Operation::Contests,
+				kind: FailureKind::NetworkFailure,
+				count: 1,
+			},
+		],
+		..Scenario::default()
+	});
+	let session = SITE.offline_session();
+	let contest = "running".to_owned();
+	for _ in 0..2 {
+		match Mock.contest_title(&session, &contest).await {
+			Err(Error::RateLimit { .. }) => (),
+			other => panic!("expected a rate limit error, got {:?}", other),
+		}
+	}
+	assert_eq!(Mock.contest_title(&session, &contest).await.unwrap(), "Mock Running Round");
+	match Mock.contests(&session).await {
+		Err(Error::SimulatedNetworkFailure) => (),
+		other => panic!("expected a network error, got {:?}", other),
+	}
+	assert_eq!(Mock.contests(&session).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_not_yet_started() {
+	set_scenario(Scenario {
+		contests: vec![MockContest {
+			id: "soon".to_owned(),
+			title: "Soon".to_owned(),
+			start: Duration::milliseconds(200),
+			tasks: vec![MockTask {
+				id: "A".to_owned(),
+				title: "Answer".to_owned(),
+				examples: Vec::new(),
+			}],
+		}],
+		..Scenario::default()
+	});
+	let session = SITE.offline_session();
+	let contest = "soon".to_owned();
+	match Mock.contest_tasks(&session, &contest).await {
+		Err(Error::NotYetStarted) => (),
+		other => panic!("expected the contest not to have started, got {:?}", other),
+	}
+	std::thread::sleep(std::time::Duration::from_millis(300));
+	let tasks = Mock.contest_tasks(&session, &contest).await.unwrap();
+	let task_url = Mock.task_url(&session, &tasks[0]).unwrap();
+	assert_eq!(task_url, "https://mock.icie.invalid/contest/soon/problem/A");
+}
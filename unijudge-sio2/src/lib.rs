@@ -1,4 +1,4 @@
-#![feature(never_type, try_blocks)]
+#![feature(try_blocks)]
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -19,9 +19,15 @@ pub struct Session {
 	username: Mutex<Option<String>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct Contest {
+	site: String,
+	id: String,
+}
+
 #[derive(Debug)]
 pub struct Task {
-	contest: String,
+	contest: Contest,
 	task: String,
 }
 
@@ -34,7 +40,7 @@ pub struct CachedAuth {
 #[async_trait(?Send)]
 impl unijudge::Backend for Sio2 {
 	type CachedAuth = CachedAuth;
-	type Contest = !;
+	type Contest = Contest;
 	type Session = Session;
 	type Task = Task;
 
@@ -50,16 +56,21 @@ impl unijudge::Backend for Sio2 {
 
 	fn deconstruct_resource(
 		&self,
-		_domain: &str,
+		domain: &str,
 		segments: &[&str],
 	) -> Result<Resource<Self::Contest, Self::Task>>
 	{
-		let (contest, task) = match segments {
-			["c", contest, "p", task] => (contest, task),
-			["c", contest, "p", task, ..] => (contest, task),
-			_ => return Err(Error::WrongTaskUrl),
-		};
-		Ok(Resource::Task(Task { contest: (*contest).to_owned(), task: (*task).to_owned() }))
+		let site = format!("https://{}", domain);
+		match segments {
+			["c", contest] | ["c", contest, "p"] | ["c", contest, "dashboard"] => {
+				Ok(Resource::Contest(Contest { site, id: (*contest).to_owned() }))
+			},
+			["c", contest, "p", task] | ["c", contest, "p", task, ..] => Ok(Resource::Task(Task {
+				contest: Contest { site, id: (*contest).to_owned() },
+				task: (*task).to_owned(),
+			})),
+			_ => Err(Error::WrongTaskUrl),
+		}
 	}
 
 	fn connect(&self, client: Client, domain: &str) -> Self::Session {
@@ -126,8 +137,8 @@ impl unijudge::Backend for Sio2 {
 		unijudge::serialize_auth(auth)
 	}
 
-	fn task_contest(&self, _: &Self::Task) -> Option<Self::Contest> {
-		None
+	fn task_contest(&self, task: &Self::Task) -> Option<Self::Contest> {
+		Some(task.contest.clone())
 	}
 
 	async fn task_details(
@@ -136,27 +147,19 @@ impl unijudge::Backend for Sio2 {
 		task: &Self::Task,
 	) -> Result<TaskDetails>
 	{
-		let url: Url = format!("{}/c/{}/p/", session.site, task.contest).parse()?;
-		let resp = session.client.get(url.clone()).send().await?;
-		if resp.url() != &url {
-			return Err(Error::AccessDenied);
-		}
-		let problems = debris::Document::new(&resp.text().await?)
-			.find(".main-content > div > table > tbody")?
-			.find_all("tr")
-			.filter(|tr| tr.child(3).is_ok())
-			.map(|tr| Ok((tr.child(1)?.text().string(), tr.find("a")?.text().string())))
-			.collect::<Result<Vec<_>>>()?;
+		let problems = fetch_problems(session, &task.contest.id).await?;
 		let title = match problems.into_iter().find(|(id, _)| id == &task.task) {
 			Some((_, title)) => title,
 			None => return Err(Error::WrongData),
 		};
-		let url2: Url = format!("{}/c/{}/p/{}/", session.site, task.contest, task.task).parse()?;
+		let url: Url = format!("{}/c/{}/p/", session.site, task.contest.id).parse()?;
+		let url2: Url =
+			format!("{}/c/{}/p/{}/", session.site, task.contest.id, task.task).parse()?;
 		let resp2 = session.client.get(url2).send().await?;
 		let statement = if resp2.headers().get(CONTENT_TYPE)
 			== Some(&HeaderValue::from_static("application/pdf"))
 		{
-			let pdf = resp2.bytes().await?.as_ref().to_owned();
+			let pdf = resp2.bytes().await?;
 			Some(Statement::PDF { pdf })
 		} else {
 			let doc2 = Document::new(&resp2.text().await?);
@@ -185,11 +188,17 @@ impl unijudge::Backend for Sio2 {
 		Ok(TaskDetails {
 			id: task.task.clone(),
 			title,
-			contest_id: task.contest.clone(),
+			contest_id: task.contest.id.clone(),
 			site_short: "sio2".to_owned(),
 			examples: None,
 			statement,
 			url: url.to_string(),
+			// The limits are only stated in the statement, which is usually a PDF.
+			time_limit: None,
+			memory_limit: None,
+			input_file: None,
+			output_file: None,
+			interactive: false,
 		})
 	}
 
@@ -199,7 +208,7 @@ impl unijudge::Backend for Sio2 {
 		task: &Self::Task,
 	) -> Result<Vec<Language>>
 	{
-		let url: Url = format!("{}/c/{}/submit/", session.site, task.contest).parse()?;
+		let url: Url = format!("{}/c/{}/submit/", session.site, task.contest.id).parse()?;
 		let resp = session.client.get(url).send().await?;
 		let doc = debris::Document::new(&resp.text().await?);
 		if doc.find("#id_password").is_ok() {
@@ -218,7 +227,7 @@ impl unijudge::Backend for Sio2 {
 		task: &Self::Task,
 	) -> Result<Vec<Submission>>
 	{
-		let url: Url = format!("{}/c/{}/submissions/", session.site, task.contest).parse()?;
+		let url: Url = format!("{}/c/{}/submissions/", session.site, task.contest.id).parse()?;
 		let resp = session.client.get(url).send().await?;
 		let doc = debris::Document::new(&resp.text().await?);
 		Ok(doc
@@ -280,6 +289,8 @@ impl unijudge::Backend for Sio2 {
 						},
 						(_, None) => Verdict::Pending { test: None },
 					},
+					language: None,
+					time: None,
 				})
 			})
 			.collect::<Result<_>>()?)
@@ -293,7 +304,7 @@ impl unijudge::Backend for Sio2 {
 		code: &str,
 	) -> Result<String>
 	{
-		let url: Url = format!("{}/c/{}/submit/", session.site, task.contest).parse()?;
+		let url: Url = format!("{}/c/{}/submit/", session.site, task.contest.id).parse()?;
 		let resp = session.client.get(url.clone()).send().await?;
 		// Workaround for https://github.com/rust-lang/rust/issues/57478.
 		let (problem_instance_id, csrf, is_admin) = {
@@ -346,49 +357,78 @@ impl unijudge::Backend for Sio2 {
 	}
 
 	fn submission_url(&self, sess: &Self::Session, task: &Self::Task, id: &str) -> String {
-		format!("{}/c{}/s/{}/", sess.site, task.contest, id)
+		format!("{}/c/{}/s/{}/", sess.site, task.contest.id, id)
 	}
 
 	fn task_url(&self, sess: &Self::Session, task: &Self::Task) -> Result<String> {
-		Ok(format!("{}/c/{}/p/{}/", sess.site, task.contest, task.task))
+		Ok(format!("{}/c/{}/p/{}/", sess.site, task.contest.id, task.task))
 	}
 
 	fn contest_id(&self, contest: &Self::Contest) -> String {
-		*contest
+		contest.id.clone()
 	}
 
 	fn contest_site_prefix(&self) -> &'static str {
-		unimplemented!()
+		"sio2"
 	}
 
 	async fn contest_tasks(
 		&self,
-		_session: &Self::Session,
+		session: &Self::Session,
 		contest: &Self::Contest,
 	) -> Result<Vec<Self::Task>>
 	{
-		*contest
+		let problems = fetch_problems(session, &contest.id).await?;
+		if problems.is_empty() {
+			return Err(Error::NotYetStarted);
+		}
+		Ok(problems.into_iter().map(|(task, _)| Task { contest: contest.clone(), task }).collect())
 	}
 
 	fn contest_url(&self, contest: &Self::Contest) -> String {
-		*contest
+		format!("{}/c/{}/", contest.site, contest.id)
 	}
 
 	async fn contest_title(
 		&self,
-		_session: &Self::Session,
+		session: &Self::Session,
 		contest: &Self::Contest,
 	) -> Result<String>
 	{
-		*contest
+		let url: Url = self.contest_url(contest).parse()?;
+		let resp = session.client.get(url).send().await?;
+		if resp.url().path().starts_with("/login") {
+			return Err(Error::AccessDenied);
+		}
+		let doc = debris::Document::new(&resp.text().await?);
+		// The navigation bar links to the current contest, using its full name.
+		Ok(doc.find(&format!("nav a[href=\"/c/{}/\"]", contest.id))?.text().string())
 	}
 
 	async fn contests(
 		&self,
-		_session: &Self::Session,
+		session: &Self::Session,
 	) -> Result<Vec<ContestDetails<Self::Contest>>>
 	{
-		Ok(Vec::new())
+		let url: Url = format!("{}/contest/", session.site).parse()?;
+		let resp = session.client.get(url).send().await?;
+		let doc = debris::Document::new(&resp.text().await?);
+		let mut contests: Vec<ContestDetails<Contest>> = Vec::new();
+		for a in doc.find_all(".main-content a[href^=\"/c/\"]") {
+			let id = a.attr("href")?.map(|href| match href.split('/').nth(2) {
+				Some(id) if !id.is_empty() => Ok(id.to_owned()),
+				_ => Err("malformed contest href"),
+			})?;
+			if contests.iter().all(|details| details.id.id != id) {
+				contests.push(ContestDetails {
+					id: Contest { site: session.site.clone(), id },
+					title: a.text().string(),
+					// sio2 does not publish contest schedules.
+					start: None,
+				});
+			}
+		}
+		Ok(contests)
 	}
 
 	fn name_short(&self) -> &'static str {
@@ -396,8 +436,22 @@ impl unijudge::Backend for Sio2 {
 	}
 
 	fn supports_contests(&self) -> bool {
-		false
+		true
+	}
+}
+
+async fn fetch_problems(session: &Session, contest: &str) -> Result<Vec<(String, String)>> {
+	let url: Url = format!("{}/c/{}/p/", session.site, contest).parse()?;
+	let resp = session.client.get(url.clone()).send().await?;
+	if resp.url() != &url {
+		return Err(Error::AccessDenied);
 	}
+	debris::Document::new(&resp.text().await?)
+		.find(".main-content > div > table > tbody")?
+		.find_all("tr")
+		.filter(|tr| tr.child(3).is_ok())
+		.map(|tr| Ok((tr.child(1)?.text().string(), tr.find("a")?.text().string())))
+		.collect()
 }
 
 impl Session {
@@ -0,0 +1,84 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Statement, Verdict};
+use unijudge_sio2::Sio2;
+
+const SITE: TestSite<Sio2> =
+	TestSite { backend: &Sio2, domain: "sio2.mimuw.edu.pl", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+#[test]
+fn test_deconstruct_url() {
+	let session = SITE.offline_session();
+	let task_url = Sio2.task_url(&session, &SITE.task("https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/"));
+	assert_eq!(task_url.unwrap(), "https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/");
+	// The session determines the domain, so tasks from other sio2 instances are not distinguished.
+	let other = SITE.task("https://szkopul.edu.pl/c/oi27-1/p/dzi/statement/");
+	let other_url = Sio2.task_url(&session, &other).unwrap();
+	assert_eq!(other_url, "https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/");
+	let contest = SITE.contest("https://sio2.mimuw.edu.pl/c/oi27-1/dashboard/");
+	assert_eq!(Sio2.contest_url(&contest), "https://sio2.mimuw.edu.pl/c/oi27-1/");
+	assert!(Sio2.deconstruct_url("https://sio2.mimuw.edu.pl/c/oi27-1/submissions/").is_err());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = SITE.task("https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/");
+	let details = Sio2.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "dzi");
+	assert_eq!(details.title, "Dzielniki");
+	assert_eq!(details.contest_id, "oi27-1");
+	match details.statement {
+		Some(Statement::PDF { pdf }) => assert!(pdf.starts_with(b"%PDF-1.4")),
+		_ => panic!("expected a PDF statement"),
+	}
+}
+
+#[tokio::test]
+async fn test_contests() {
+	let contests = Sio2.contests(&SITE.session("contests.json")).await.unwrap();
+	assert_eq!(contests.len(), 2);
+	assert_eq!(Sio2.contest_id(&contests[0].id), "oi27-1");
+	assert_eq!(contests[0].title, "XXVII OI, Etap I");
+	assert_eq!(Sio2.contest_url(&contests[1].id), "https://sio2.mimuw.edu.pl/c/pa2019/");
+	assert_eq!(contests[1].title, "Potyczki Algorytmiczne 2019");
+	assert!(contests.iter().all(|contest| contest.start.is_none()));
+}
+
+#[tokio::test]
+async fn test_contest_title() {
+	let contest = SITE.contest("https://sio2.mimuw.edu.pl/c/oi27-1/");
+	let title = Sio2.contest_title(&SITE.session("contest_title.json"), &contest).await.unwrap();
+	assert_eq!(title, "XXVII OI, Etap I");
+}
+
+#[tokio::test]
+async fn test_contest_tasks() {
+	let session = SITE.session("contest_tasks.json");
+	let contest = SITE.contest("https://sio2.mimuw.edu.pl/c/oi27-1/");
+	let tasks = Sio2.contest_tasks(&session, &contest).await.unwrap();
+	let urls = tasks.iter().map(|task| Sio2.task_url(&session, task).unwrap()).collect::<Vec<_>>();
+	assert_eq!(urls, [
+		"https://sio2.mimuw.edu.pl/c/oi27-1/p/cuk/",
+		"https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/"
+	]);
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let task = SITE.task("https://sio2.mimuw.edu.pl/c/oi27-1/p/dzi/");
+	let submissions =
+		Sio2.task_submissions(&SITE.session("task_submissions.json"), &task).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "391204");
+	assert_eq!(submissions[0].verdict, Verdict::Pending { test: None });
+	assert_eq!(submissions[1].id, "391187");
+	assert_eq!(submissions[1].verdict, Verdict::Scored {
+		score: 36.0,
+		max: None,
+		cause: Some(RejectionCause::TimeLimitExceeded),
+		test: None
+	});
+	assert_eq!(submissions[2].id, "391150");
+	assert_eq!(submissions[2].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::CompilationError),
+		test: None
+	});
+}
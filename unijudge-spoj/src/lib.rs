@@ -2,7 +2,7 @@
 
 use async_trait::async_trait;
 use unijudge::{
-	self, debris::{self, Context, Find}, http::{Client, Cookie}, reqwest::{
+	self, chrono::{FixedOffset, TimeZone}, debris::{self, Context, Find}, http::{Client, Cookie}, reqwest::{
 		header::{ORIGIN, REFERER}, multipart
 	}, url::Url, ContestDetails, Error, Language, RejectionCause, Resource, Result, Submission, TaskDetails, Verdict
 };
@@ -104,6 +104,22 @@ impl unijudge::Backend for SPOJ {
 		let resp = session.get(url.clone()).send().await?;
 		let doc = debris::Document::new(&resp.text().await?);
 		let title = doc.find(".breadcrumb > .active")?.text().string();
+		let mut time_limit = None;
+		let mut memory_limit = None;
+		for row in doc.find_all("#problem-meta > tbody > tr") {
+			let (key, value) = match (row.child(0), row.child(1)) {
+				(Ok(key), Ok(value)) => (key.text().string(), value.text().string()),
+				_ => continue,
+			};
+			if key.starts_with("Time limit") {
+				time_limit = unijudge::parse_time_limit(&value);
+			} else if key.starts_with("Memory limit") {
+				memory_limit = unijudge::parse_memory_limit(&value);
+			}
+		}
+		let interactive = doc
+			.find_all("#problem-tags a")
+			.any(|tag| tag.text().string().trim_start_matches('#') == "interactive");
 		let mut statement = unijudge::statement::Rewrite::start(doc);
 		statement.fix_hide(|v| {
 			if let unijudge::scraper::Node::Element(v) = v.value() {
@@ -135,6 +151,11 @@ impl unijudge::Backend for SPOJ {
 			examples: None,
 			statement: Some(statement.export()),
 			url: url.to_string(),
+			time_limit,
+			memory_limit,
+			input_file: None,
+			output_file: None,
+			interactive,
 		})
 	}
 
@@ -160,6 +181,9 @@ impl unijudge::Backend for SPOJ {
 		_task: &Self::Task,
 	) -> Result<Vec<Submission>>
 	{
+		// Times are shown in the Polish time zone, approximated here as CET regardless of daylight
+		// saving time.
+		let central_european_time = FixedOffset::east(3600);
 		let user = req_user(session)?;
 		let url: Url = format!("https://www.spoj.com/status/{}/", user).parse()?;
 		let resp = session.get(url).send().await?;
@@ -216,6 +240,10 @@ impl unijudge::Backend for SPOJ {
 								}),
 						}
 					})?,
+					language: Some(row.find(".slang")?.text().string()),
+					time: Some(row.find(".status_sm")?.text().map(|time| {
+						central_european_time.datetime_from_str(time, "%Y-%m-%d %H:%M:%S")
+					})?),
 				})
 			})
 			.collect::<Result<_>>()?)
@@ -266,6 +294,22 @@ impl unijudge::Backend for SPOJ {
 		format!("https://www.spoj.com/status/{}/", task)
 	}
 
+	async fn submission_source(
+		&self,
+		session: &Self::Session,
+		_task: &Self::Task,
+		id: &str,
+	) -> Result<String>
+	{
+		req_user(session)?;
+		let url: Url = format!("https://www.spoj.com/files/src/plain/{}/", id).parse()?;
+		let resp = session.get(url.clone()).send().await?;
+		if *resp.url() != url {
+			return Err(Error::AccessDenied);
+		}
+		Ok(resp.text().await?)
+	}
+
 	fn contest_id(&self, contest: &Self::Contest) -> String {
 		*contest
 	}
@@ -0,0 +1,79 @@
+use unijudge::{testing::TestSite, Backend, RejectionCause, Resource, Verdict};
+use unijudge_spoj::SPOJ;
+
+const SITE: TestSite<SPOJ> =
+	TestSite { backend: &SPOJ, domain: "www.spoj.com", manifest_dir: env!("CARGO_MANIFEST_DIR") };
+
+fn task(url: &str) -> String {
+	match SITE.resource(url) {
+		Resource::Task(task) => task,
+		Resource::Contest(contest) => contest,
+	}
+}
+
+#[test]
+fn test_deconstruct_url() {
+	assert_eq!(task("https://www.spoj.com/problems/TEST/"), "TEST");
+	assert!(SPOJ.deconstruct_url("https://www.spoj.com/status/TEST/").is_err());
+	assert!(SPOJ.deconstruct_url("https://spoj.pl/problems/TEST/").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_task_details() {
+	let task = task("https://www.spoj.com/problems/TEST/");
+	let details = SPOJ.task_details(&SITE.session("task_details.json"), &task).await.unwrap();
+	assert_eq!(details.id, "TEST");
+	assert_eq!(details.title, "Life, the Universe, and Everything");
+	assert_eq!(details.time_limit, Some(std::time::Duration::from_secs(10)));
+	assert_eq!(details.memory_limit, Some(1536 * 1024 * 1024));
+	assert!(!details.interactive);
+}
+
+#[tokio::test]
+async fn test_contests() {
+	// SPOJ does not list contests, so no requests should be made.
+	let contests = SPOJ.contests(&SITE.session("contests.json")).await.unwrap();
+	assert!(contests.is_empty());
+}
+
+#[tokio::test]
+async fn test_task_submissions() {
+	let session = SITE.session("task_submissions.json");
+	let auth = SPOJ
+		.auth_deserialize(
+			r#"[{"cookie":"SPOJ=c1e5"},{"cookie":"autologin_login=tester"},{"cookie":"autologin_hash=9f2a"}]"#,
+		)
+		.unwrap();
+	SPOJ.auth_restore(&session, &auth).await.unwrap();
+	let submissions = SPOJ.task_submissions(&session, &"TEST".to_owned()).await.unwrap();
+	assert_eq!(submissions.len(), 3);
+	assert_eq!(submissions[0].id, "24911375");
+	assert_eq!(submissions[0].verdict, Verdict::Rejected {
+		cause: Some(RejectionCause::WrongAnswer),
+		test: None
+	});
+	assert_eq!(submissions[1].id, "24911342");
+	assert_eq!(submissions[1].verdict, Verdict::Accepted);
+	assert_eq!(submissions[2].id, "24911301");
+	assert_eq!(submissions[2].verdict, Verdict::Scored {
+		score: 37.5,
+		max: None,
+		cause: None,
+		test: None
+	});
+	assert_eq!(submissions[1].language.as_deref(), Some("C++14-CLANG"));
+	assert_eq!(submissions[1].time.unwrap().to_rfc3339(), "2020-01-15T17:42:00+01:00");
+}
+
+#[tokio::test]
+async fn test_submission_source() {
+	let session = SITE.session("submission_source.json");
+	let auth = SPOJ
+		.auth_deserialize(
+			r#"[{"cookie":"SPOJ=c1e5"},{"cookie":"autologin_login=tester"},{"cookie":"autologin_hash=9f2a"}]"#,
+		)
+		.unwrap();
+	SPOJ.auth_restore(&session, &auth).await.unwrap();
+	let source = SPOJ.submission_source(&session, &"TEST".to_owned(), "24911342").await.unwrap();
+	assert!(source.starts_with("#include <iostream>\n"));
+}
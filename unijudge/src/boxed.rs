@@ -1,5 +1,5 @@
 use crate::{
-	http::Client, ContestDetails, Language, Resource, Result, Submission, TaskDetails, URL
+	http::Client, ContestDetails, Invocation, Language, Resource, Result, Standings, Submission, TaskDetails, URL
 };
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -112,6 +112,28 @@ impl crate::Backend for (dyn DynamicBackend+'static) {
 		self.task_submitx(session.0.deref(), task.0.deref(), language, code).await
 	}
 
+	async fn invocation_start(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		language: &Language,
+		code: &str,
+		input: &str,
+	) -> Result<String>
+	{
+		self.invocation_startx(session.0.deref(), task.0.deref(), language, code, input).await
+	}
+
+	async fn invocation_result(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		id: &str,
+	) -> Result<Option<Invocation>>
+	{
+		self.invocation_resultx(session.0.deref(), task.0.deref(), id).await
+	}
+
 	fn task_url(&self, session: &Self::Session, task: &Self::Task) -> Result<String> {
 		self.task_urlx(session.0.deref(), task.0.deref())
 	}
@@ -120,6 +142,16 @@ impl crate::Backend for (dyn DynamicBackend+'static) {
 		self.submission_urlx(session.0.deref(), task.0.deref(), id)
 	}
 
+	async fn submission_source(
+		&self,
+		session: &Self::Session,
+		task: &Self::Task,
+		id: &str,
+	) -> Result<String>
+	{
+		self.submission_sourcex(session.0.deref(), task.0.deref(), id).await
+	}
+
 	fn contest_id(&self, contest: &Self::Contest) -> String {
 		self.contest_idx(contest.0.deref())
 	}
@@ -158,6 +190,24 @@ impl crate::Backend for (dyn DynamicBackend+'static) {
 		self.contestsx(session.0.deref()).await
 	}
 
+	async fn contest_standings(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Standings>
+	{
+		self.contest_standingsx(session.0.deref(), contest.0.deref()).await
+	}
+
+	async fn contest_submissions(
+		&self,
+		session: &Self::Session,
+		contest: &Self::Contest,
+	) -> Result<Vec<Submission>>
+	{
+		self.contest_submissionsx(session.0.deref(), contest.0.deref()).await
+	}
+
 	fn name_short(&self) -> &'static str {
 		self.name_shortx()
 	}
@@ -205,8 +255,28 @@ pub trait DynamicBackend: Debug+Send+Sync {
 		language: &Language,
 		code: &str,
 	) -> Result<String>;
+	async fn invocation_startx(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		language: &Language,
+		code: &str,
+		input: &str,
+	) -> Result<String>;
+	async fn invocation_resultx(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		id: &str,
+	) -> Result<Option<Invocation>>;
 	fn task_urlx(&self, session: &dyn AnyDebug, task: &dyn AnyDebug) -> Result<String>;
 	fn submission_urlx(&self, session: &dyn AnyDebug, task: &dyn AnyDebug, id: &str) -> String;
+	async fn submission_sourcex(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		id: &str,
+	) -> Result<String>;
 	fn contest_idx(&self, contest: &dyn AnyDebug) -> String;
 	fn contest_site_prefixx(&self) -> &'static str;
 	async fn contest_tasksx(
@@ -221,6 +291,16 @@ pub trait DynamicBackend: Debug+Send+Sync {
 		contest: &dyn AnyDebug,
 	) -> Result<String>;
 	async fn contestsx(&self, session: &dyn AnyDebug) -> Result<Vec<BoxedContestDetails>>;
+	async fn contest_standingsx(
+		&self,
+		session: &dyn AnyDebug,
+		contest: &dyn AnyDebug,
+	) -> Result<Standings>;
+	async fn contest_submissionsx(
+		&self,
+		session: &dyn AnyDebug,
+		contest: &dyn AnyDebug,
+	) -> Result<Vec<Submission>>;
 	fn name_shortx(&self) -> &'static str;
 	fn supports_contestsx(&self) -> bool;
 }
@@ -345,6 +425,42 @@ where
 		.await
 	}
 
+	async fn invocation_startx(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		language: &Language,
+		code: &str,
+		input: &str,
+	) -> Result<String>
+	{
+		<T as crate::Backend>::invocation_start(
+			self,
+			ujcast::<T::Session>(session),
+			ujcast::<T::Task>(task),
+			language,
+			code,
+			input,
+		)
+		.await
+	}
+
+	async fn invocation_resultx(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		id: &str,
+	) -> Result<Option<Invocation>>
+	{
+		<T as crate::Backend>::invocation_result(
+			self,
+			ujcast::<T::Session>(session),
+			ujcast::<T::Task>(task),
+			id,
+		)
+		.await
+	}
+
 	fn task_urlx(&self, session: &dyn AnyDebug, task: &dyn AnyDebug) -> Result<String> {
 		<T as crate::Backend>::task_url(
 			self,
@@ -362,6 +478,22 @@ where
 		)
 	}
 
+	async fn submission_sourcex(
+		&self,
+		session: &dyn AnyDebug,
+		task: &dyn AnyDebug,
+		id: &str,
+	) -> Result<String>
+	{
+		<T as crate::Backend>::submission_source(
+			self,
+			ujcast::<T::Session>(session),
+			ujcast::<T::Task>(task),
+			id,
+		)
+		.await
+	}
+
 	fn contest_idx(&self, contest: &dyn AnyDebug) -> String {
 		<T as crate::Backend>::contest_id(self, ujcast::<T::Contest>(contest))
 	}
@@ -417,6 +549,34 @@ where
 			.collect())
 	}
 
+	async fn contest_standingsx(
+		&self,
+		session: &dyn AnyDebug,
+		contest: &dyn AnyDebug,
+	) -> Result<Standings>
+	{
+		<T as crate::Backend>::contest_standings(
+			self,
+			ujcast::<T::Session>(session),
+			ujcast::<T::Contest>(contest),
+		)
+		.await
+	}
+
+	async fn contest_submissionsx(
+		&self,
+		session: &dyn AnyDebug,
+		contest: &dyn AnyDebug,
+	) -> Result<Vec<Submission>>
+	{
+		<T as crate::Backend>::contest_submissions(
+			self,
+			ujcast::<T::Session>(session),
+			ujcast::<T::Contest>(contest),
+		)
+		.await
+	}
+
 	fn name_shortx(&self) -> &'static str {
 		<T as crate::Backend>::name_short(self)
 	}
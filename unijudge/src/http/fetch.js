@@ -0,0 +1,68 @@
+// Network settings for the fetch function of Node.js, which is provided by node-fetch.
+// Requests of clients with custom settings carry a header with the id of an agent registered here,
+// which is removed before the request is passed to node-fetch along with the agent.
+// Modules are only loaded when an agent is created, so that this file can be loaded without them.
+
+const AGENT_HEADER = 'x-unijudge-agent';
+
+const agents = new Map();
+let nextId = 0;
+let originalFetch = undefined;
+
+function createAgent(proxy, rootCertificate, timeout) {
+	installFetch();
+	const ca = rootCertificate === undefined ? undefined : (require('tls').rootCertificates || []).concat([rootCertificate]);
+	let http = undefined;
+	let https = ca === undefined ? undefined : new (require('https').Agent)({ ca });
+	if (proxy !== undefined) {
+		const protocol = new URL(proxy).protocol;
+		if (protocol === 'http:' || protocol === 'https:') {
+			http = require('http-proxy-agent')(proxy);
+			https = withCertificate(require('https-proxy-agent')(proxy), ca);
+		} else if (protocol === 'socks5:' || protocol === 'socks5h:') {
+			http = withCertificate(require('socks-proxy-agent')(proxy), ca);
+			https = http;
+		} else {
+			throw new Error(`unsupported proxy protocol ${protocol}`);
+		}
+	}
+	const id = nextId++;
+	agents.set(id, { http, https, timeout });
+	return id;
+}
+
+function dropAgent(id) {
+	agents.delete(id);
+}
+
+// Proxy agents open the TLS connections themselves, using the options of the request.
+function withCertificate(agent, ca) {
+	if (ca !== undefined) {
+		const addRequest = agent.addRequest;
+		agent.addRequest = (request, options) => addRequest.call(agent, request, Object.assign({}, options, { ca }));
+	}
+	return agent;
+}
+
+function installFetch() {
+	if (originalFetch !== undefined) {
+		return;
+	}
+	originalFetch = global.fetch;
+	global.fetch = (input, init) => {
+		const request = new originalFetch.Request(input, init);
+		const id = request.headers.get(AGENT_HEADER);
+		if (id === null) {
+			return originalFetch(request);
+		}
+		request.headers.delete(AGENT_HEADER);
+		const settings = agents.get(Number(id));
+		if (settings === undefined) {
+			return Promise.reject(new Error('request made by a dropped client'));
+		}
+		const agent = url => url.protocol === 'https:' ? settings.https : settings.http;
+		return originalFetch(request, { agent, timeout: settings.timeout });
+	};
+}
+
+module.exports = { createAgent, dropAgent };
@@ -0,0 +1,52 @@
+//! Network settings on WebAssembly, where requests are made by the fetch function of the JavaScript
+//! runtime. The settings are applied by a wrapper around it in `fetch.js`, which picks the agent
+//! of a request by the id in a header added to all requests of the client.
+
+use crate::{http::Settings, Error, Result};
+use reqwest::header::{HeaderName, HeaderValue};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/src/http/fetch.js")]
+extern "C" {
+	#[wasm_bindgen(js_name = createAgent, catch)]
+	fn create_agent(
+		proxy: Option<String>,
+		root_certificate: Option<String>,
+		timeout: Option<f64>,
+	) -> std::result::Result<u32, JsValue>;
+	#[wasm_bindgen(js_name = dropAgent)]
+	fn drop_agent(id: u32);
+}
+
+/// Agent registered in the JavaScript runtime, which should live as long as the client using it.
+#[derive(Debug)]
+pub struct Agent {
+	id: u32,
+}
+
+impl Agent {
+	pub fn new(settings: &Settings) -> Result<Agent> {
+		let root_certificate = match &settings.root_certificate {
+			Some(pem) => Some(String::from_utf8(pem.clone()).map_err(|_| Error::WrongData)?),
+			None => None,
+		};
+		let id = create_agent(
+			settings.proxy.as_ref().map(|proxy| proxy.to_string()),
+			root_certificate,
+			settings.timeout.map(|timeout| timeout.as_millis() as f64),
+		)
+		.map_err(|_| Error::WrongData)?;
+		Ok(Agent { id })
+	}
+
+	/// Header which makes the request use this agent.
+	pub fn header(&self) -> (HeaderName, HeaderValue) {
+		(HeaderName::from_static("x-unijudge-agent"), HeaderValue::from(self.id))
+	}
+}
+
+impl Drop for Agent {
+	fn drop(&mut self) {
+		drop_agent(self.id);
+	}
+}
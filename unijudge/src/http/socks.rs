@@ -0,0 +1,333 @@
+//! SOCKS5 support for the HTTP client. The pinned reqwest version only supports HTTP proxies, so
+//! requests are sent to a small HTTP proxy on a loopback port, which opens every connection through
+//! the SOCKS5 proxy. Host names are always resolved by the SOCKS5 proxy.
+
+use crate::{Error, Result};
+use percent_encoding::percent_decode_str;
+use std::{
+	collections::hash_map::RandomState, hash::{BuildHasher, Hasher}, io::{self, Read, Write}, net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream}, sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst}, Arc
+	}, thread
+};
+use url::{Position, Url};
+
+/// Connections handled at the same time, each of which takes two threads. Further connections are
+/// refused until some of them finish.
+const CONNECTION_LIMIT: usize = 16;
+/// Username the bridge expects in the basic auth sent by the client, with the token as password.
+const USERNAME: &str = "unijudge";
+
+#[derive(Clone, Debug)]
+struct Socks {
+	address: String,
+	credentials: Option<(String, String)>,
+}
+
+/// Local HTTP proxy forwarding connections through a SOCKS5 proxy, which should live as long as
+/// the client using it. Other processes can connect to the loopback port too, so only requests
+/// authenticated with a random token generated for this client are accepted.
+#[derive(Debug)]
+pub struct Bridge {
+	address: SocketAddr,
+	token: String,
+	stopped: Arc<AtomicBool>,
+}
+
+impl Bridge {
+	pub fn start(url: &Url) -> Result<Bridge> {
+		let socks = parse(url)?;
+		let listener = TcpListener::bind("127.0.0.1:0").map_err(|_| Error::StateCorruption)?;
+		let address = listener.local_addr().map_err(|_| Error::StateCorruption)?;
+		let token = random_token();
+		let authorization =
+			format!("Basic {}", base64(format!("{}:{}", USERNAME, token).as_bytes()));
+		let stopped = Arc::new(AtomicBool::new(false));
+		let active = Arc::new(AtomicUsize::new(0));
+		let stopped2 = stopped.clone();
+		thread::spawn(move || {
+			for client in listener.incoming() {
+				if stopped2.load(SeqCst) {
+					break;
+				}
+				let mut client = match client {
+					Ok(client) => client,
+					Err(_) => continue,
+				};
+				if active.fetch_add(1, SeqCst) >= CONNECTION_LIMIT {
+					active.fetch_sub(1, SeqCst);
+					let _ = respond(&mut client, "503 Service Unavailable", "too many connections");
+					continue;
+				}
+				let socks = socks.clone();
+				let authorization = authorization.clone();
+				let active = active.clone();
+				thread::spawn(move || {
+					handle(client, &socks, &authorization);
+					active.fetch_sub(1, SeqCst);
+				});
+			}
+		});
+		Ok(Bridge { address, token, stopped })
+	}
+
+	/// Proxy configuration which sends the requests to the bridge.
+	pub fn proxy(&self) -> Result<reqwest::Proxy> {
+		Ok(reqwest::Proxy::all(&format!("http://{}", self.address))
+			.map_err(|_| Error::WrongData)?
+			.basic_auth(USERNAME, &self.token))
+	}
+}
+
+impl Drop for Bridge {
+	fn drop(&mut self) {
+		self.stopped.store(true, SeqCst);
+		// The listening thread is blocked waiting for a connection, so one is made to wake it up.
+		let _ = TcpStream::connect(self.address);
+	}
+}
+
+fn parse(url: &Url) -> Result<Socks> {
+	let host = url.host_str().ok_or(Error::WrongData)?;
+	let address = format!("{}:{}", host, url.port().unwrap_or(1080));
+	let credentials = if url.username().is_empty() {
+		None
+	} else {
+		let username = percent_decode_str(url.username()).decode_utf8_lossy().into_owned();
+		let password =
+			percent_decode_str(url.password().unwrap_or("")).decode_utf8_lossy().into_owned();
+		// The username/password authentication stores both lengths in a single byte.
+		if username.len() > 255 || password.len() > 255 {
+			return Err(Error::WrongData);
+		}
+		Some((username, password))
+	};
+	Ok(Socks { address, credentials })
+}
+
+fn handle(mut client: TcpStream, socks: &Socks, authorization: &str) {
+	let result = match read_head(&mut client) {
+		Ok(head) => match strip_authorization(&head, authorization) {
+			Some(head) => forward(&mut client, &head, socks),
+			None => respond(&mut client, "407 Proxy Authentication Required", "wrong credentials"),
+		},
+		Err(e) => Err(e),
+	};
+	if let Err(e) = result {
+		let _ = respond(&mut client, "502 Bad Gateway", &e.to_string());
+	}
+}
+
+fn respond(client: &mut TcpStream, status: &str, message: &str) -> io::Result<()> {
+	write!(
+		client,
+		"HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+		status,
+		message.len(),
+		message
+	)
+}
+
+/// Checks the credentials of the request, and removes them so that they are not sent any further.
+/// Connection headers are removed too, as only the first request of a connection is checked.
+fn strip_authorization(head: &str, authorization: &str) -> Option<String> {
+	let mut authorized = false;
+	let mut stripped = String::new();
+	for line in head.trim_end_matches("\r\n").split("\r\n") {
+		let name = line.find(':').map(|i| (&line[..i], &line[i + 1..]));
+		match name {
+			Some((name, value)) if name.eq_ignore_ascii_case("proxy-authorization") => {
+				authorized |= value.trim() == authorization;
+			},
+			Some((name, _))
+				if name.eq_ignore_ascii_case("connection")
+					|| name.eq_ignore_ascii_case("proxy-connection") => {},
+			_ => {
+				stripped += line;
+				stripped += "\r\n";
+			},
+		}
+	}
+	if authorized { Some(stripped + "\r\n") } else { None }
+}
+
+/// Opens the connection requested by the HTTP client, and then passes the data both ways until one
+/// of the sides closes it.
+fn forward(client: &mut TcpStream, head: &str, socks: &Socks) -> io::Result<()> {
+	let (request_line, rest) = head.split_at(head.find("\r\n").unwrap_or(head.len()));
+	let mut parts = request_line.split(' ');
+	let (method, target, version) = match (parts.next(), parts.next(), parts.next()) {
+		(Some(method), Some(target), Some(version)) => (method, target, version),
+		_ => return Err(invalid("malformed HTTP request")),
+	};
+	let mut server = if method == "CONNECT" {
+		let url =
+			Url::parse(&format!("http://{}", target)).map_err(|_| invalid("malformed host"))?;
+		let server = connect(socks, &url)?;
+		client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")?;
+		server
+	} else {
+		// Requests to proxies contain the whole URL, while servers expect only the path.
+		let url = Url::parse(target).map_err(|_| invalid("malformed URL"))?;
+		let mut server = connect(socks, &url)?;
+		// Closing the connection makes the client send its next request on a new, checked one.
+		let rest = rest.trim_end_matches("\r\n");
+		write!(
+			server,
+			"{} {} {}{}\r\nConnection: close\r\n\r\n",
+			method,
+			&url[Position::BeforePath..],
+			version,
+			rest
+		)?;
+		server
+	};
+	let mut client_read = client.try_clone()?;
+	let mut server_write = server.try_clone()?;
+	let upload = thread::spawn(move || {
+		let _ = io::copy(&mut client_read, &mut server_write);
+		let _ = server_write.shutdown(Shutdown::Write);
+	});
+	let _ = io::copy(&mut server, client);
+	let _ = client.shutdown(Shutdown::Write);
+	let _ = upload.join();
+	Ok(())
+}
+
+/// Reads the request head byte by byte, so that nothing after it is consumed.
+fn read_head(client: &mut TcpStream) -> io::Result<String> {
+	let mut head = Vec::new();
+	let mut byte = [0];
+	while !head.ends_with(b"\r\n\r\n") {
+		client.read_exact(&mut byte)?;
+		head.push(byte[0]);
+	}
+	String::from_utf8(head).map_err(|_| invalid("malformed HTTP request"))
+}
+
+/// Connects to the server through the SOCKS5 proxy, as described in RFC 1928 and RFC 1929.
+fn connect(socks: &Socks, url: &Url) -> io::Result<TcpStream> {
+	let host = url.host_str().ok_or_else(|| invalid("missing host"))?;
+	let port = url.port_or_known_default().ok_or_else(|| invalid("missing port"))?;
+	let mut stream = TcpStream::connect(&socks.address)?;
+	let method = if socks.credentials.is_some() { 2 } else { 0 };
+	stream.write_all(&[5, 1, method])?;
+	let mut reply = [0; 2];
+	stream.read_exact(&mut reply)?;
+	if reply != [5, method] {
+		return Err(invalid("SOCKS5 proxy does not accept the authentication method"));
+	}
+	if let Some((username, password)) = &socks.credentials {
+		let mut request = vec![1, username.len() as u8];
+		request.extend_from_slice(username.as_bytes());
+		request.push(password.len() as u8);
+		request.extend_from_slice(password.as_bytes());
+		stream.write_all(&request)?;
+		stream.read_exact(&mut reply)?;
+		if reply[1] != 0 {
+			return Err(invalid("SOCKS5 proxy rejected the credentials"));
+		}
+	}
+	let mut request = vec![5, 1, 0];
+	match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+		Ok(IpAddr::V4(ip)) => {
+			request.push(1);
+			request.extend_from_slice(&ip.octets());
+		},
+		Ok(IpAddr::V6(ip)) => {
+			request.push(4);
+			request.extend_from_slice(&ip.octets());
+		},
+		Err(_) if host.len() <= 255 => {
+			request.extend_from_slice(&[3, host.len() as u8]);
+			request.extend_from_slice(host.as_bytes());
+		},
+		Err(_) => return Err(invalid("host name too long")),
+	}
+	request.extend_from_slice(&port.to_be_bytes());
+	stream.write_all(&request)?;
+	let mut reply = [0; 4];
+	stream.read_exact(&mut reply)?;
+	if reply[1] != 0 {
+		return Err(invalid(&format!("SOCKS5 proxy could not connect to {}:{}", host, port)));
+	}
+	// The reply ends with the address the proxy used for the connection, which is not needed.
+	let address_len = match reply[3] {
+		1 => 4,
+		4 => 16,
+		3 => {
+			let mut len = [0];
+			stream.read_exact(&mut len)?;
+			len[0] as usize
+		},
+		_ => return Err(invalid("malformed SOCKS5 reply")),
+	};
+	stream.read_exact(&mut vec![0; address_len + 2])?;
+	Ok(stream)
+}
+
+fn invalid(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::Other, message)
+}
+
+/// Generates a token which other processes can not guess, using the random keys of the standard
+/// library hash maps.
+fn random_token() -> String {
+	let mut token = String::new();
+	for _ in 0..2 {
+		token += &format!("{:016x}", RandomState::new().build_hasher().finish());
+	}
+	token
+}
+
+fn base64(data: &[u8]) -> String {
+	const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut encoded = String::new();
+	for chunk in data.chunks(3) {
+		let bits = chunk
+			.iter()
+			.enumerate()
+			.fold(0, |bits, (i, byte)| bits | (*byte as u32) << (16 - 8 * i));
+		for i in 0..4 {
+			if i <= chunk.len() {
+				encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 63) as usize] as char);
+			} else {
+				encoded.push('=');
+			}
+		}
+	}
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_base64() {
+		assert_eq!(base64(b"unijudge:t"), "dW5panVkZ2U6dA==");
+		assert_eq!(base64(b"unijudge:to"), "dW5panVkZ2U6dG8=");
+		assert_eq!(base64(b"unijudge:tok"), "dW5panVkZ2U6dG9r");
+	}
+
+	#[test]
+	fn test_strip_authorization() {
+		let head = "GET http://judge.invalid/ HTTP/1.1\r\nProxy-Authorization: Basic \
+		            abc\r\nProxy-Connection: keep-alive\r\nAccept: */*\r\n\r\n";
+		assert_eq!(
+			strip_authorization(head, "Basic abc").as_deref(),
+			Some("GET http://judge.invalid/ HTTP/1.1\r\nAccept: */*\r\n\r\n")
+		);
+		assert_eq!(strip_authorization(head, "Basic xyz"), None);
+		assert_eq!(strip_authorization("GET / HTTP/1.1\r\n\r\n", "Basic abc"), None);
+	}
+
+	#[test]
+	fn test_bridge_requires_authorization() {
+		let bridge = Bridge::start(&"socks5://127.0.0.1:1".parse().unwrap()).unwrap();
+		let mut stream = TcpStream::connect(bridge.address).unwrap();
+		stream.write_all(b"CONNECT judge.invalid:443 HTTP/1.1\r\n\r\n").unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).unwrap();
+		assert!(response.starts_with("HTTP/1.1 407 "));
+	}
+}
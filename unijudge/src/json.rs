@@ -1,5 +1,4 @@
-use crate::{Error, Result};
-use reqwest::Response;
+use crate::{http::Response, Error, Result};
 use serde::{de::DeserializeOwned, Deserialize};
 
 pub async fn from_resp<T: DeserializeOwned>(resp: Response, endpoint: &'static str) -> Result<T> {
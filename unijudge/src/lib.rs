@@ -15,11 +15,14 @@ pub mod http;
 pub mod json;
 #[macro_use]
 pub mod statement;
+pub mod testing;
 
 use async_trait::async_trait;
 use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::fmt::{self, Debug};
+use std::{
+	fmt::{self, Debug}, time::Duration
+};
 use url::Url;
 
 #[derive(Debug)]
@@ -29,8 +32,15 @@ pub enum Error {
 	WrongTaskUrl,
 	AccessDenied,
 	NotYetStarted,
-	RateLimit,
+	RateLimit {
+		/// Delay requested by the site before trying again, if it specified one.
+		retry_after: Option<Duration>,
+	},
+	UnsupportedFeature,
 	NetworkFailure(reqwest::Error),
+	/// Network failure simulated by a mock backend, which should be handled like
+	/// [`Error::NetworkFailure`].
+	SimulatedNetworkFailure,
 	NoTLS(reqwest::Error),
 	URLParseFailure(url::ParseError),
 	StateCorruption,
@@ -70,8 +80,12 @@ impl fmt::Display for Error {
 			Error::WrongTaskUrl => f.write_str("wrong task URL format"),
 			Error::AccessDenied => f.write_str("access denied"),
 			Error::NotYetStarted => f.write_str("contest not yet started"),
-			Error::RateLimit => f.write_str("rate limited due to too frequent network operations"),
+			Error::RateLimit { .. } => {
+				f.write_str("rate limited due to too frequent network operations")
+			},
+			Error::UnsupportedFeature => f.write_str("feature not supported by this site"),
 			Error::NetworkFailure(_) => f.write_str("network failure"),
+			Error::SimulatedNetworkFailure => f.write_str("simulated network failure"),
 			Error::NoTLS(_) => f.write_str("could not initialize TLS on this system"),
 			Error::URLParseFailure(_) => f.write_str("URL parse failure"),
 			Error::StateCorruption => f.write_str("network agent corrupted due to earlier panic"),
@@ -89,8 +103,10 @@ impl std::error::Error for Error {
 			Error::WrongTaskUrl => None,
 			Error::AccessDenied => None,
 			Error::NotYetStarted => None,
-			Error::RateLimit => None,
+			Error::RateLimit { .. } => None,
+			Error::UnsupportedFeature => None,
 			Error::NetworkFailure(e) => Some(e),
+			Error::SimulatedNetworkFailure => None,
 			Error::NoTLS(e) => Some(e),
 			Error::URLParseFailure(e) => Some(e),
 			Error::StateCorruption => None,
@@ -133,6 +149,14 @@ pub struct TaskDetails {
 	pub examples: Option<Vec<Example>>,
 	pub statement: Option<Statement>,
 	pub url: String,
+	pub time_limit: Option<Duration>,
+	/// Memory limit in bytes.
+	pub memory_limit: Option<u64>,
+	/// Name of the file the input is read from, or `None` if standard input is used.
+	pub input_file: Option<String>,
+	/// Name of the file the output is written to, or `None` if standard output is used.
+	pub output_file: Option<String>,
+	pub interactive: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -157,6 +181,9 @@ pub enum RejectionCause {
 pub struct Submission {
 	pub id: String,
 	pub verdict: Verdict,
+	/// Name of the language, as displayed by the site.
+	pub language: Option<String>,
+	pub time: Option<DateTime<FixedOffset>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -169,11 +196,49 @@ pub enum Verdict {
 	Glitch,
 }
 
+/// Result of running code on the judge's servers with a custom input, without submitting it.
+#[derive(Clone, Debug)]
+pub struct Invocation {
+	pub output: String,
+	pub time: Option<Duration>,
+	/// Peak memory usage in bytes.
+	pub memory: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Standings {
+	/// Symbols of the contest tasks, in the same order as the results in each row.
+	pub tasks: Vec<String>,
+	pub rows: Vec<StandingsRow>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StandingsRow {
+	pub rank: u64,
+	pub participant: String,
+	pub points: f64,
+	/// Penalty time, for sites which break ties with it.
+	pub penalty: Option<Duration>,
+	/// Results for each task, or `None` if the task was not attempted.
+	pub results: Vec<Option<TaskResult>>,
+	/// Whether the row belongs to the logged in user.
+	pub own: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskResult {
+	/// Points earned, which is 1 for a solved task if the contest does not use scoring.
+	pub points: f64,
+	pub rejected: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct ContestDetails<I> {
 	pub id: I,
 	pub title: String,
-	pub start: DateTime<FixedOffset>,
+	/// Start time, if the site publishes contest schedules. Contests without one are treated as
+	/// already started.
+	pub start: Option<DateTime<FixedOffset>>,
 }
 
 #[derive(Clone, Debug)]
@@ -261,8 +326,42 @@ pub trait Backend: Debug+Send+Sync+'static {
 		language: &Language,
 		code: &str,
 	) -> Result<String>;
+	/// Starts running the code on the given input on the judge's servers, returning an ID to pass
+	/// to [`Backend::invocation_result`]. Most sites do not support this.
+	async fn invocation_start(
+		&self,
+		_session: &Self::Session,
+		_task: &Self::Task,
+		_language: &Language,
+		_code: &str,
+		_input: &str,
+	) -> Result<String>
+	{
+		Err(Error::UnsupportedFeature)
+	}
+	/// Checks the result of a run started with [`Backend::invocation_start`], returning `None` if
+	/// it has not finished yet.
+	async fn invocation_result(
+		&self,
+		_session: &Self::Session,
+		_task: &Self::Task,
+		_id: &str,
+	) -> Result<Option<Invocation>>
+	{
+		Err(Error::UnsupportedFeature)
+	}
 	fn task_url(&self, session: &Self::Session, task: &Self::Task) -> Result<String>;
 	fn submission_url(&self, session: &Self::Session, task: &Self::Task, id: &str) -> String;
+	/// Fetches the source code of a past submission. Most sites do not support this.
+	async fn submission_source(
+		&self,
+		_session: &Self::Session,
+		_task: &Self::Task,
+		_id: &str,
+	) -> Result<String>
+	{
+		Err(Error::UnsupportedFeature)
+	}
 	fn contest_id(&self, contest: &Self::Contest) -> String;
 	fn contest_site_prefix(&self) -> &'static str;
 	async fn contest_tasks(
@@ -278,6 +377,26 @@ pub trait Backend: Debug+Send+Sync+'static {
 	) -> Result<String>;
 	async fn contests(&self, session: &Self::Session)
 	-> Result<Vec<ContestDetails<Self::Contest>>>;
+	/// Fetches the top of the contest scoreboard, including the logged in user's row if they
+	/// participated. Most sites do not support this.
+	async fn contest_standings(
+		&self,
+		_session: &Self::Session,
+		_contest: &Self::Contest,
+	) -> Result<Standings>
+	{
+		Err(Error::UnsupportedFeature)
+	}
+	/// Fetches the logged in user's submissions to all tasks of the contest, which saves requests
+	/// when tracking many of them. Most sites do not support this.
+	async fn contest_submissions(
+		&self,
+		_session: &Self::Session,
+		_contest: &Self::Contest,
+	) -> Result<Vec<Submission>>
+	{
+		Err(Error::UnsupportedFeature)
+	}
 	fn name_short(&self) -> &'static str;
 	fn supports_contests(&self) -> bool;
 }
@@ -300,3 +419,68 @@ pub fn deserialize_auth<'d, T: Deserialize<'d>>(data: &'d str) -> Result<T> {
 pub fn serialize_auth<T: Serialize>(auth: &T) -> Result<String> {
 	serde_json::to_string(auth).map_err(|_| Error::WrongData)
 }
+
+/// Parses limits like "2 seconds", "1.5 s" or "500 ms", using the first number found in the text.
+pub fn parse_time_limit(text: &str) -> Option<Duration> {
+	let (value, unit) = parse_quantity(text)?;
+	let multiplier = match unit.as_str() {
+		"ms" | "millisecond" | "milliseconds" => 0.001,
+		"" | "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+		_ => return None,
+	};
+	Some(Duration::from_secs_f64(value * multiplier))
+}
+
+/// Parses limits like "256 megabytes", "1024 MB" or "64 MiB" into bytes, using the first number
+/// found in the text.
+pub fn parse_memory_limit(text: &str) -> Option<u64> {
+	let (value, unit) = parse_quantity(text)?;
+	let multiplier = match unit.as_str() {
+		"kb" | "kib" | "kilobyte" | "kilobytes" => 1024.0,
+		"m" | "mb" | "mib" | "megabyte" | "megabytes" => 1024.0 * 1024.0,
+		"g" | "gb" | "gib" | "gigabyte" | "gigabytes" => 1024.0 * 1024.0 * 1024.0,
+		_ => return None,
+	};
+	Some((value * multiplier) as u64)
+}
+
+fn parse_quantity(text: &str) -> Option<(f64, String)> {
+	let start = text.find(|c: char| c.is_ascii_digit())?;
+	let text = &text[start..];
+	let end = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+	let value = text[..end].parse().ok()?;
+	let unit = text[end..]
+		.trim_start()
+		.chars()
+		.take_while(|c| c.is_alphabetic())
+		.collect::<String>()
+		.to_lowercase();
+	Some((value, unit))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_time_limit() {
+		assert_eq!(parse_time_limit("time limit per test2 seconds"), Some(Duration::from_secs(2)));
+		assert_eq!(parse_time_limit("Time Limit: 1.5 sec "), Some(Duration::from_millis(1500)));
+		assert_eq!(parse_time_limit("500 ms"), Some(Duration::from_millis(500)));
+		assert_eq!(parse_time_limit("3"), Some(Duration::from_secs(3)));
+		assert_eq!(parse_time_limit("CPU Time limit 1 second"), Some(Duration::from_secs(1)));
+		assert_eq!(parse_time_limit("2 minutes"), None);
+		assert_eq!(parse_time_limit("unknown"), None);
+	}
+
+	#[test]
+	fn test_parse_memory_limit() {
+		assert_eq!(parse_memory_limit("memory limit per test256 megabytes"), Some(256 << 20));
+		assert_eq!(parse_memory_limit(" Memory Limit: 1024 MB"), Some(1024 << 20));
+		assert_eq!(parse_memory_limit("64 MiB"), Some(64 << 20));
+		assert_eq!(parse_memory_limit("1.5 GB"), Some(3 << 29));
+		assert_eq!(parse_memory_limit("65536 KB"), Some(64 << 20));
+		assert_eq!(parse_memory_limit("256"), None);
+		assert_eq!(parse_memory_limit("no limit"), None);
+	}
+}
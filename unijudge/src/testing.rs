@@ -0,0 +1,53 @@
+//! Helpers for backend tests, which replay site responses saved in fixture files instead of
+//! accessing the network.
+
+use crate::{
+	http::{Client, Settings}, Backend, Resource
+};
+use std::path::Path;
+
+pub const USER_AGENT: &str = "unijudge-tests";
+
+/// Site used in the tests of a backend crate. Fixtures are read from the tests/fixtures directory
+/// of the crate, so `manifest_dir` should be set to `env!("CARGO_MANIFEST_DIR")`.
+#[derive(Debug)]
+pub struct TestSite<B: 'static> {
+	pub backend: &'static B,
+	pub domain: &'static str,
+	pub manifest_dir: &'static str,
+}
+
+impl<B: Backend> TestSite<B> {
+	/// Connects with a client replaying the given fixture, see [`Client::fixture`].
+	pub fn session(&self, fixture: &str) -> B::Session {
+		let path = Path::new(self.manifest_dir).join("tests/fixtures").join(fixture);
+		self.backend.connect(Client::fixture(USER_AGENT, path).unwrap(), self.domain)
+	}
+
+	/// Connects with a regular client, for tests which do not access the network at all.
+	pub fn offline_session(&self) -> B::Session {
+		self.backend.connect(Client::new(USER_AGENT, &Settings::default()).unwrap(), self.domain)
+	}
+
+	pub fn resource(&self, url: &str) -> Resource<B::Contest, B::Task> {
+		self.backend
+			.deconstruct_url(url)
+			.unwrap()
+			.expect("expected a URL of the tested site")
+			.resource
+	}
+
+	pub fn task(&self, url: &str) -> B::Task {
+		match self.resource(url) {
+			Resource::Task(task) => task,
+			Resource::Contest(_) => panic!("expected a task URL"),
+		}
+	}
+
+	pub fn contest(&self, url: &str) -> B::Contest {
+		match self.resource(url) {
+			Resource::Contest(contest) => contest,
+			Resource::Task(_) => panic!("expected a contest URL"),
+		}
+	}
+}
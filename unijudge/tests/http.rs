@@ -0,0 +1,118 @@
+use std::{
+	io::{Read, Write}, net::{SocketAddr, TcpListener}, thread, time::Duration
+};
+use unijudge::{
+	http::{Client, Settings}, Error
+};
+
+const USER_AGENT: &str = "unijudge-tests";
+
+/// Starts a server on a local port, which answers a single request and returns the request head.
+fn serve_once(response: &'static [u8]) -> (SocketAddr, thread::JoinHandle<String>) {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+	let handle = thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		let mut head = Vec::new();
+		let mut buf = [0; 1024];
+		while !head.ends_with(b"\r\n\r\n") {
+			let len = stream.read(&mut buf).unwrap();
+			if len == 0 {
+				break;
+			}
+			head.extend_from_slice(&buf[..len]);
+		}
+		stream.write_all(response).unwrap();
+		String::from_utf8(head).unwrap()
+	});
+	(address, handle)
+}
+
+#[tokio::test]
+async fn test_proxy() {
+	let (address, proxy) =
+		serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+	let proxy_url = format!("http://alice:s%40cret@{}", address).parse().unwrap();
+	let settings = Settings { proxy: Some(proxy_url), ..Settings::default() };
+	let client = Client::new(USER_AGENT, &settings).unwrap();
+	let resp = client.get("http://judge.invalid/ping".parse().unwrap()).send().await.unwrap();
+	assert_eq!(resp.text().await.unwrap(), "ok");
+	let head = proxy.join().unwrap();
+	assert!(head.starts_with("GET http://judge.invalid/ping HTTP/1.1\r\n"));
+	assert!(head.contains("proxy-authorization: Basic YWxpY2U6c0BjcmV0\r\n"));
+}
+
+#[tokio::test]
+async fn test_charset() {
+	let (address, server) = serve_once(
+		b"HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=ISO-8859-2\r\nContent-Length: \
+		  5\r\nConnection: close\r\n\r\nZa\xbf\xf3\xb3",
+	);
+	let client = Client::new(USER_AGENT, &Settings::default()).unwrap();
+	let url = format!("http://{}/", address).parse().unwrap();
+	let resp = client.get(url).send().await.unwrap();
+	assert_eq!(resp.text().await.unwrap(), "Zażół");
+	server.join().unwrap();
+}
+
+#[tokio::test]
+async fn test_proxy_socks5() {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+	let proxy = thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		let mut greeting = [0; 3];
+		stream.read_exact(&mut greeting).unwrap();
+		assert_eq!(greeting, [5, 1, 2]);
+		stream.write_all(&[5, 2]).unwrap();
+		let mut auth = [0; 14];
+		stream.read_exact(&mut auth).unwrap();
+		assert_eq!(&auth, b"\x01\x05alice\x06s@cret");
+		stream.write_all(&[1, 0]).unwrap();
+		let mut connect = [0; 20];
+		stream.read_exact(&mut connect).unwrap();
+		assert_eq!(&connect, b"\x05\x01\x00\x03\x0djudge.invalid\x00\x50");
+		stream.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0]).unwrap();
+		let mut head = Vec::new();
+		let mut byte = [0];
+		while !head.ends_with(b"\r\n\r\n") {
+			stream.read_exact(&mut byte).unwrap();
+			head.push(byte[0]);
+		}
+		stream
+			.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+			.unwrap();
+		String::from_utf8(head).unwrap()
+	});
+	let proxy_url = format!("socks5://alice:s%40cret@{}", address).parse().unwrap();
+	let settings = Settings { proxy: Some(proxy_url), ..Settings::default() };
+	let client = Client::new(USER_AGENT, &settings).unwrap();
+	let resp = client.get("http://judge.invalid/ping".parse().unwrap()).send().await.unwrap();
+	assert_eq!(resp.text().await.unwrap(), "ok");
+	let head = proxy.join().unwrap();
+	assert!(head.starts_with("GET /ping HTTP/1.1\r\n"));
+	assert!(!head.to_lowercase().contains("proxy-authorization"));
+}
+
+#[test]
+fn test_proxy_unsupported() {
+	let settings =
+		Settings { proxy: Some("ftp://127.0.0.1:21".parse().unwrap()), ..Settings::default() };
+	match Client::new(USER_AGENT, &settings) {
+		Err(Error::UnsupportedFeature) => (),
+		other => panic!("expected an unsupported feature error, got {:?}", other),
+	}
+}
+
+#[tokio::test]
+async fn test_timeout() {
+	// The connection is accepted by the operating system, but nothing ever responds.
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let url = format!("http://{}/", listener.local_addr().unwrap()).parse().unwrap();
+	let settings = Settings { timeout: Some(Duration::from_millis(200)), ..Settings::default() };
+	let client = Client::new(USER_AGENT, &settings).unwrap();
+	match client.get(url).send().await {
+		Err(Error::NetworkFailure(e)) => assert!(e.is_timeout()),
+		other => panic!("expected a timeout, got {:?}", other),
+	}
+}